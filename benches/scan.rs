@@ -0,0 +1,113 @@
+//! Scanning benchmark: times `mdlibs list` and `mdlibs search` on a generated library,
+//! once with a single thread and once with several.
+//!
+//! Run with `cargo bench`. `MDLIBS_BENCH_FILES` sets the number of documents
+//! (default 50000), `MDLIBS_BENCH_THREADS` the thread count compared against one
+//! thread (default 0, one per CPU) and `MDLIBS_BENCH_RUNS` the runs per measurement
+//! (default 3).
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Documents per generated directory
+const FILES_PER_DIR: usize = 100;
+
+fn env_usize(name: &str, default: usize) -> usize {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Write a library of `count` documents spread over nested directories
+fn generate_library(root: &Path, count: usize) -> io::Result<()> {
+    let _ = fs::remove_dir_all(root);
+    fs::create_dir_all(root.join("templates"))?;
+    fs::write(root.join("templates/note.md"), "# Note\n\nTemplate body.\n")?;
+
+    for index in 0..count {
+        let dir = root.join(format!(
+            "docs/area-{:03}/topic-{:03}",
+            index / (FILES_PER_DIR * 10),
+            index / FILES_PER_DIR
+        ));
+        if index % FILES_PER_DIR == 0 {
+            fs::create_dir_all(&dir)?;
+        }
+        let needle = if index % 97 == 0 { "needle" } else { "hay" };
+        fs::write(
+            dir.join(format!("doc-{:06}.md", index)),
+            format!(
+                "# Document {}\n\n## Overview\n\nSome {} text about document {}.\n\n```rust\nfn main() {{}}\n```\n",
+                index, needle, index
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+fn write_config(root: &Path, threads: usize) -> io::Result<()> {
+    fs::write(
+        root.join(".mdlibs.toml"),
+        format!(
+            "[library]\nname = \"bench\"\n\n[walk]\nthreads = {}\n",
+            threads
+        ),
+    )
+}
+
+/// Fastest of `runs` runs of an mdlibs command in the library
+fn time_command(root: &Path, args: &[&str], runs: usize) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            let status = Command::new(env!("CARGO_BIN_EXE_mdlibs"))
+                .args(args)
+                .current_dir(root)
+                .stdout(Stdio::null())
+                .status()
+                .expect("failed to run mdlibs");
+            assert!(status.success(), "mdlibs {:?} failed", args);
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn main() -> io::Result<()> {
+    let files = env_usize("MDLIBS_BENCH_FILES", 50_000);
+    let threads = env_usize("MDLIBS_BENCH_THREADS", 0);
+    let runs = env_usize("MDLIBS_BENCH_RUNS", 3).max(1);
+    let root = env::temp_dir().join("mdlibs_bench_library");
+
+    println!("Generating {} documents in {}", files, root.display());
+    generate_library(&root, files)?;
+
+    let benchmarks: [(&str, &[&str]); 2] = [("list", &["list"]), ("search", &["search", "needle"])];
+    for (name, args) in benchmarks {
+        write_config(&root, 1)?;
+        let serial = time_command(&root, args, runs);
+        write_config(&root, threads)?;
+        let parallel = time_command(&root, args, runs);
+        println!(
+            "{:<8} 1 thread: {:>8.1} ms   {} threads: {:>8.1} ms   speedup: {:.2}x",
+            name,
+            serial.as_secs_f64() * 1000.0,
+            if threads == 0 {
+                String::from("all")
+            } else {
+                threads.to_string()
+            },
+            parallel.as_secs_f64() * 1000.0,
+            serial.as_secs_f64() / parallel.as_secs_f64()
+        );
+    }
+
+    // Cleanup
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
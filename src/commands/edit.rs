@@ -0,0 +1,307 @@
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::commands::index;
+use crate::commands::list::{collect_documents, DocumentEntry};
+use crate::commands::update::find_document_candidates;
+use crate::config::LibraryConfig;
+use crate::date::Date;
+use crate::frontmatter;
+use crate::snapshot;
+use crate::storage::{self, LibraryLock};
+use crate::utils::{extract_title_from_file, fuzzy_score};
+
+/// Maximum number of candidates offered by the interactive picker
+const MAX_CANDIDATES: usize = 10;
+
+/// Open a document in the user's editor
+pub fn run(query: &str) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    let doc_path = resolve_document(&lib_root, query)?;
+    let before = fs::read_to_string(&doc_path)?;
+    snapshot::record(&lib_root, &doc_path)?;
+
+    launch_editor(&doc_path)?;
+
+    // The lock is only taken once the editor has exited, so it is not held while editing
+    let _lock = LibraryLock::acquire(&lib_root)?;
+    let after = storage::read_document(&doc_path)?;
+    if after.content == before {
+        println!("No changes to: {}", doc_path.display());
+        return Ok(());
+    }
+
+    // Keep an existing `updated` field in sync with the edit
+    if frontmatter::get(&after.content, "updated").is_some() {
+        let stamped = frontmatter::set(&after.content, "updated", &Date::today().to_string());
+        if stamped != after.content {
+            storage::write_document(&lib_root, &after, &stamped)?;
+        }
+    }
+
+    println!("Updated document: {}", doc_path.display());
+
+    // A generated index page lists titles, which the edit may have changed
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+    let index_relative = Path::new(&config.index.path);
+    if lib_root.join(index_relative).is_file() {
+        let group_by = index::configured_group_by(&config)?;
+        if index::refresh(&lib_root, index_relative, group_by)?.0 == index::Refresh::Updated {
+            println!("Updated index: {}", lib_root.join(index_relative).display());
+        }
+    }
+    Ok(())
+}
+
+/// Resolve a query to a document, by exact path/name/title first and then by fuzzy match
+/// over titles and paths, asking the user to choose when the match is ambiguous
+fn resolve_document(lib_root: &Path, query: &str) -> io::Result<PathBuf> {
+    // A rejected path (outside the library, not markdown) is an error, not a fuzzy query
+    let exact = match find_document_candidates(lib_root, query) {
+        Ok(exact) => exact,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    if exact.len() == 1 {
+        return Ok(exact[0].clone());
+    }
+    if exact.len() > 1 {
+        let lib_canonical = lib_root.canonicalize()?;
+        let options: Vec<(PathBuf, String)> = exact
+            .into_iter()
+            .map(|path| {
+                let relative = path.strip_prefix(&lib_canonical).unwrap_or(&path);
+                let title = extract_title_from_file(&path).unwrap_or_default();
+                let label = format!("{} ({})", title, relative.display());
+                (path, label)
+            })
+            .collect();
+        return choose(query, options);
+    }
+
+    let documents = collect_documents(lib_root)?;
+    let candidates = rank_candidates(&documents, query);
+
+    match candidates.as_slice() {
+        [] => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No document matches: {}", query),
+        )),
+        [(_, only)] => Ok(lib_root.join(&only.path)),
+        [(best_score, best), (second_score, _), ..]
+            if best_score > second_score && is_exact(best, query) =>
+        {
+            Ok(lib_root.join(&best.path))
+        }
+        _ => {
+            let options = candidates
+                .iter()
+                .take(MAX_CANDIDATES)
+                .map(|(_, doc)| {
+                    (
+                        lib_root.join(&doc.path),
+                        format!("{} ({})", doc.title, doc.path),
+                    )
+                })
+                .collect();
+            choose(query, options)
+        }
+    }
+}
+
+/// Let the user pick one of several matching documents, or fail with the list of
+/// candidates when stdin is not a terminal
+fn choose(query: &str, mut options: Vec<(PathBuf, String)>) -> io::Result<PathBuf> {
+    let labels: Vec<&str> = options.iter().map(|(_, label)| label.as_str()).collect();
+    if !io::stdin().is_terminal() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "'{}' matches several documents:\n  {}",
+                query,
+                labels.join("\n  ")
+            ),
+        ));
+    }
+
+    let stdin = io::stdin();
+    let idx = pick(&labels, query, &mut stdin.lock(), &mut io::stderr())?;
+    Ok(options.swap_remove(idx).0)
+}
+
+/// Score every document against the query, best match first
+fn rank_candidates<'a>(
+    documents: &'a [DocumentEntry],
+    query: &str,
+) -> Vec<(i64, &'a DocumentEntry)> {
+    let mut candidates: Vec<(i64, &DocumentEntry)> = documents
+        .iter()
+        .filter_map(|doc| {
+            let score = match (
+                fuzzy_score(query, &doc.title),
+                fuzzy_score(query, &doc.path),
+            ) {
+                (Some(a), Some(b)) => a.max(b),
+                (a, b) => a.or(b)?,
+            };
+            Some((score, doc))
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.path.cmp(&b.1.path)));
+    candidates
+}
+
+/// Whether the query names the document's title or file name exactly (ignoring case)
+fn is_exact(doc: &DocumentEntry, query: &str) -> bool {
+    let query = query.to_lowercase();
+    let stem = Path::new(&doc.path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    doc.title.to_lowercase() == query || stem == query
+}
+
+/// Ask the user to choose one of the labelled candidates, returning its index
+fn pick(
+    labels: &[&str],
+    query: &str,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<usize> {
+    writeln!(output, "Several documents match '{}':", query)?;
+    for (idx, label) in labels.iter().enumerate() {
+        writeln!(output, "  {:>2}) {}", idx + 1, label)?;
+    }
+    write!(output, "Select a document [1-{}]: ", labels.len())?;
+    output.flush()?;
+
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    line.trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .filter(|&idx| idx < labels.len())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid selection: {}", line.trim()),
+            )
+        })
+}
+
+/// The editor command from `$VISUAL` or `$EDITOR`, falling back to `vi`
+fn editor_command() -> Vec<String> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|value| {
+            value
+                .split_whitespace()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+        })
+        .find(|parts| !parts.is_empty())
+        .unwrap_or_else(|| vec![String::from("vi")])
+}
+
+/// Run the editor on a file and wait for it to exit
+pub fn launch_editor(path: &Path) -> io::Result<()> {
+    let command = editor_command();
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .arg(path)
+        .status()
+        .map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("Failed to launch editor '{}': {}", command[0], e),
+            )
+        })?;
+
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "Editor '{}' exited with {}",
+            command[0], status
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::list::DocumentType;
+    use std::env;
+
+    fn entry(path: &str, title: &str) -> DocumentEntry {
+        DocumentEntry {
+            path: path.to_string(),
+            title: title.to_string(),
+            doc_type: DocumentType::Document,
+        }
+    }
+
+    #[test]
+    fn test_rank_candidates() {
+        let docs = vec![
+            entry("docs/install.md", "Installation Guide"),
+            entry("docs/setup/linux.md", "Linux Setup"),
+            entry("docs/other.md", "Unrelated"),
+        ];
+        let ranked = rank_candidates(&docs, "linux");
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].1.path, "docs/setup/linux.md");
+
+        let ranked = rank_candidates(&docs, "inst");
+        assert_eq!(ranked[0].1.path, "docs/install.md");
+    }
+
+    #[test]
+    fn test_pick() {
+        let labels = ["Alpha (a.md)", "Beta (b.md)"];
+
+        let mut output = Vec::new();
+        let picked = pick(&labels, "a", &mut "2\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(picked, 1);
+        let shown = String::from_utf8(output).unwrap();
+        assert!(shown.contains(" 1) Alpha (a.md)"));
+
+        let result = pick(&labels, "a", &mut "7\n".as_bytes(), &mut Vec::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_resolve_document_by_title() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_edit_resolve");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs/guides")).unwrap();
+        fs::write(
+            temp_dir.join("docs/guides/setup.md"),
+            "# Getting Started\n\nContent",
+        )
+        .unwrap();
+        fs::write(temp_dir.join("docs/notes.md"), "# Notes\n").unwrap();
+
+        let path = resolve_document(&temp_dir, "getting started").unwrap();
+        assert!(path.ends_with("docs/guides/setup.md"));
+
+        let result = resolve_document(&temp_dir, "zzz");
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+
+        // A path outside the library is rejected rather than fuzzy matched
+        fs::write(temp_dir.join("notes.txt"), "Notes").unwrap();
+        let result = resolve_document(&temp_dir.join("docs"), "../notes.txt");
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -0,0 +1,70 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::commands::list::collect_documents;
+use crate::commands::update::find_document;
+use crate::config::LibraryConfig;
+use crate::format::format;
+use crate::storage::{self, LibraryLock};
+
+/// Format documents (all documents in the library if none are given), or with `check`,
+/// only report the documents that are not formatted
+pub fn run(documents: &[String], check: bool) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+    let lib_canonical = lib_root.canonicalize()?;
+
+    let paths: Vec<PathBuf> = if documents.is_empty() {
+        collect_documents(&lib_root)?
+            .into_iter()
+            .map(|doc| lib_canonical.join(doc.path))
+            .collect()
+    } else {
+        documents
+            .iter()
+            .map(|document| find_document(&lib_root, document))
+            .collect::<io::Result<_>>()?
+    };
+
+    let _lock = if check {
+        None
+    } else {
+        Some(LibraryLock::acquire(&lib_root)?)
+    };
+
+    let mut changed = 0;
+    for path in &paths {
+        let relative = path.strip_prefix(&lib_canonical).unwrap_or(path);
+        let doc = storage::read_document(path)?;
+        let formatted = format(&doc.content, &config.fmt);
+        if formatted == doc.content {
+            continue;
+        }
+
+        changed += 1;
+        if check {
+            println!("Not formatted: {}", relative.display());
+        } else {
+            storage::write_document(&lib_root, &doc, &formatted)?;
+            println!("Formatted: {}", relative.display());
+        }
+    }
+
+    if check && changed > 0 {
+        return Err(io::Error::other(format!(
+            "{} of {} document(s) are not formatted (run 'mdlibs fmt' to format them)",
+            changed,
+            paths.len()
+        )));
+    }
+    if changed == 0 {
+        println!("All {} document(s) are formatted.", paths.len());
+    }
+
+    Ok(())
+}
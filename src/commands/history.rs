@@ -0,0 +1,52 @@
+use std::io;
+
+use crate::commands::update::find_document;
+use crate::config::LibraryConfig;
+use crate::date::Date;
+use crate::git;
+use crate::utils::truncate_display;
+
+/// Show the commits that touched a document
+pub fn run(document: &str) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    let doc_path = find_document(&lib_root, document)?;
+
+    if !git::is_repository(&lib_root) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Not a git repository: {}", lib_root.display()),
+        ));
+    }
+
+    let commits = git::file_history(&lib_root, &doc_path)?;
+    if commits.is_empty() {
+        println!("No commits found for: {}", doc_path.display());
+        println!("Hint: The document may not have been committed yet.");
+        return Ok(());
+    }
+
+    println!(
+        "History of {} ({} commit(s)):\n",
+        doc_path.display(),
+        commits.len()
+    );
+    println!("{:<10} {:<12} {:<20} SUBJECT", "COMMIT", "DATE", "AUTHOR");
+    println!("{}", "-".repeat(70));
+
+    for commit in &commits {
+        println!(
+            "{:<10} {:<12} {:<20} {}",
+            commit.short_hash(),
+            Date::from_unix_timestamp(commit.timestamp).to_string(),
+            truncate_display(&commit.author, 17),
+            commit.subject
+        );
+    }
+
+    Ok(())
+}
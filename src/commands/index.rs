@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Component, Path};
+
+use clap::ValueEnum;
+
+use crate::commands::list::{collect_documents, DocumentEntry, DocumentType};
+use crate::config::LibraryConfig;
+use crate::frontmatter;
+use crate::storage::{self, LibraryLock};
+use crate::utils::{relative_link, set_marked_block};
+
+/// Marker comments surrounding the generated index
+const INDEX_START: &str = "<!-- index -->";
+const INDEX_END: &str = "<!-- /index -->";
+
+/// Title of a newly created index document
+const INDEX_TITLE: &str = "# Index";
+
+/// Group name for documents without tags
+const UNTAGGED: &str = "Untagged";
+
+/// Group name for documents at the library root
+const ROOT_GROUP: &str = "(library root)";
+
+/// How documents are grouped in the index
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum GroupBy {
+    /// By the directory containing the document
+    Directory,
+    /// By frontmatter tag (documents with several tags appear in each group)
+    Tag,
+    /// Documents and templates
+    Type,
+}
+
+/// What refreshing the index page did
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Refresh {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// Generate or refresh the library index page
+pub fn run(group_by: Option<GroupBy>, output: Option<&str>) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+    let group_by = match group_by {
+        Some(group_by) => group_by,
+        None => configured_group_by(&config)?,
+    };
+    let index_relative = Path::new(output.unwrap_or(&config.index.path));
+    let index_path = lib_root.join(index_relative);
+
+    let _lock = LibraryLock::acquire(&lib_root)?;
+    let (refresh, count) = refresh(&lib_root, index_relative, group_by)?;
+    match refresh {
+        Refresh::Created => println!(
+            "Created index of {} document(s): {}",
+            count,
+            index_path.display()
+        ),
+        Refresh::Updated => println!(
+            "Updated index of {} document(s): {}",
+            count,
+            index_path.display()
+        ),
+        Refresh::Unchanged => println!("Index is up to date: {}", index_path.display()),
+    }
+
+    Ok(())
+}
+
+/// The `group_by` setting of the `[index]` config section
+pub fn configured_group_by(config: &LibraryConfig) -> io::Result<GroupBy> {
+    GroupBy::from_str(&config.index.group_by, true).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid index group_by in config: {} (expected directory, tag or type)",
+                config.index.group_by
+            ),
+        )
+    })
+}
+
+/// Write the index page at `index_relative` (relative to the library root), creating it
+/// if needed or regenerating its marked block. The caller holds the library lock.
+/// Returns what was done and the number of documents listed.
+pub fn refresh(
+    lib_root: &Path,
+    index_relative: &Path,
+    group_by: GroupBy,
+) -> io::Result<(Refresh, usize)> {
+    let escapes_library = index_relative.is_absolute()
+        || index_relative
+            .components()
+            .any(|c| matches!(c, Component::ParentDir));
+    if escapes_library {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "Access denied: index path must be inside the library directory",
+        ));
+    }
+    let index_path = lib_root.join(index_relative);
+
+    // The index does not list itself
+    let documents: Vec<DocumentEntry> = collect_documents(lib_root)?
+        .into_iter()
+        .filter(|doc| Path::new(&doc.path) != index_relative)
+        .collect();
+    let tags = if group_by == GroupBy::Tag {
+        read_tags(lib_root, &documents)
+    } else {
+        HashMap::new()
+    };
+    let index_dir = index_relative.parent().unwrap_or_else(|| Path::new(""));
+    let body = render_index(&documents, &tags, group_by, index_dir);
+
+    if !index_path.exists() {
+        if let Some(parent) = index_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = format!(
+            "{}\n\n{}\n{}\n{}\n",
+            INDEX_TITLE, INDEX_START, body, INDEX_END
+        );
+        storage::write_atomic(&index_path, &content)?;
+        return Ok((Refresh::Created, documents.len()));
+    }
+
+    let doc = storage::read_document(&index_path)?;
+    let updated = set_marked_block(&doc.content, INDEX_START, INDEX_END, &body);
+    if updated == doc.content {
+        return Ok((Refresh::Unchanged, documents.len()));
+    }
+    storage::write_document(lib_root, &doc, &updated)?;
+    Ok((Refresh::Updated, documents.len()))
+}
+
+/// Read the frontmatter tags of each document, keyed by path
+fn read_tags(lib_root: &Path, documents: &[DocumentEntry]) -> HashMap<String, Vec<String>> {
+    documents
+        .iter()
+        .map(|doc| {
+            let tags = fs::read_to_string(lib_root.join(&doc.path))
+                .ok()
+                .and_then(|content| frontmatter::get_list(&content, "tags"))
+                .unwrap_or_default();
+            (doc.path.clone(), tags)
+        })
+        .collect()
+}
+
+/// Names of the groups a document belongs to
+fn groups_of(
+    doc: &DocumentEntry,
+    tags: &HashMap<String, Vec<String>>,
+    group_by: GroupBy,
+) -> Vec<String> {
+    match group_by {
+        GroupBy::Directory => {
+            let dir = Path::new(&doc.path)
+                .parent()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            if dir.is_empty() {
+                vec![String::from(ROOT_GROUP)]
+            } else {
+                vec![dir]
+            }
+        }
+        GroupBy::Tag => {
+            let mut doc_tags = tags.get(&doc.path).cloned().unwrap_or_default();
+            doc_tags.sort();
+            doc_tags.dedup();
+            if doc_tags.is_empty() {
+                vec![String::from(UNTAGGED)]
+            } else {
+                doc_tags
+            }
+        }
+        GroupBy::Type => vec![String::from(match doc.doc_type {
+            DocumentType::Document => "Documents",
+            DocumentType::Template => "Templates",
+        })],
+    }
+}
+
+/// Render the index body: a section per group, each listing its documents by title
+/// with links relative to the directory of the index document.
+///
+/// Groups and documents are sorted so regenerating an unchanged library is a no-op.
+fn render_index(
+    documents: &[DocumentEntry],
+    tags: &HashMap<String, Vec<String>>,
+    group_by: GroupBy,
+    index_dir: &Path,
+) -> String {
+    let mut groups: BTreeMap<String, Vec<&DocumentEntry>> = BTreeMap::new();
+    for doc in documents {
+        for group in groups_of(doc, tags, group_by) {
+            groups.entry(group).or_default().push(doc);
+        }
+    }
+
+    // Untagged documents come last rather than in alphabetical order
+    let untagged = groups.remove(UNTAGGED);
+    let ordered = groups
+        .into_iter()
+        .chain(untagged.map(|docs| (String::from(UNTAGGED), docs)));
+
+    let mut sections = Vec::new();
+    for (group, mut docs) in ordered {
+        docs.sort_by(|a, b| {
+            a.title
+                .to_lowercase()
+                .cmp(&b.title.to_lowercase())
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        let mut section = format!("## {}\n", group);
+        for doc in docs {
+            section.push_str(&format!(
+                "\n- [{}]({})",
+                doc.title,
+                relative_link(index_dir, Path::new(&doc.path))
+            ));
+        }
+        sections.push(section);
+    }
+    sections.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, title: &str, doc_type: DocumentType) -> DocumentEntry {
+        DocumentEntry {
+            path: path.to_string(),
+            title: title.to_string(),
+            doc_type,
+        }
+    }
+
+    fn sample() -> Vec<DocumentEntry> {
+        vec![
+            entry("docs/guides/setup.md", "Setup", DocumentType::Document),
+            entry("docs/about.md", "About", DocumentType::Document),
+            entry(
+                "docs/guides/advanced.md",
+                "Advanced",
+                DocumentType::Document,
+            ),
+            entry("templates/note.md", "Note", DocumentType::Template),
+            entry("README.md", "Readme", DocumentType::Document),
+        ]
+    }
+
+    #[test]
+    fn test_render_index_by_directory() {
+        let index = render_index(
+            &sample(),
+            &HashMap::new(),
+            GroupBy::Directory,
+            Path::new("docs"),
+        );
+        assert_eq!(
+            index,
+            "## (library root)\n\n- [Readme](../README.md)\n\n\
+             ## docs\n\n- [About](about.md)\n\n\
+             ## docs/guides\n\n- [Advanced](guides/advanced.md)\n- [Setup](guides/setup.md)\n\n\
+             ## templates\n\n- [Note](../templates/note.md)"
+        );
+    }
+
+    #[test]
+    fn test_render_index_by_tag() {
+        let tags = HashMap::from([
+            (
+                String::from("docs/guides/setup.md"),
+                vec![String::from("start"), String::from("guide")],
+            ),
+            (
+                String::from("docs/guides/advanced.md"),
+                vec![String::from("guide")],
+            ),
+        ]);
+        let index = render_index(&sample()[..3], &tags, GroupBy::Tag, Path::new(""));
+        assert_eq!(
+            index,
+            "## guide\n\n- [Advanced](docs/guides/advanced.md)\n- [Setup](docs/guides/setup.md)\n\n\
+             ## start\n\n- [Setup](docs/guides/setup.md)\n\n\
+             ## Untagged\n\n- [About](docs/about.md)"
+        );
+    }
+
+    #[test]
+    fn test_render_index_by_type() {
+        let index = render_index(&sample(), &HashMap::new(), GroupBy::Type, Path::new(""));
+        assert!(index.starts_with("## Documents\n\n- [About](docs/about.md)\n"));
+        assert!(index.ends_with("## Templates\n\n- [Note](templates/note.md)"));
+    }
+
+    #[test]
+    fn test_regenerating_preserves_surrounding_text() {
+        let body = render_index(&sample(), &HashMap::new(), GroupBy::Type, Path::new(""));
+        let content = format!(
+            "# Map\n\nIntro text.\n\n{}\nstale\n{}\n\nFooter.\n",
+            INDEX_START, INDEX_END
+        );
+        let updated = set_marked_block(&content, INDEX_START, INDEX_END, &body);
+        assert!(updated.starts_with("# Map\n\nIntro text.\n\n<!-- index -->\n## Documents\n"));
+        assert!(updated.ends_with("<!-- /index -->\n\nFooter.\n"));
+        assert_eq!(
+            set_marked_block(&updated, INDEX_START, INDEX_END, &body),
+            updated
+        );
+    }
+
+    #[test]
+    fn test_refresh() {
+        let temp_dir = std::env::temp_dir().join("mdlibs_test_index_refresh");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs")).unwrap();
+        fs::write(temp_dir.join("docs/a.md"), "# Alpha\n").unwrap();
+        let index = Path::new("docs/index.md");
+
+        let refresh_index = || refresh(&temp_dir, index, GroupBy::Directory).unwrap();
+        assert_eq!(refresh_index(), (Refresh::Created, 1));
+        assert_eq!(refresh_index(), (Refresh::Unchanged, 1));
+        fs::write(temp_dir.join("docs/a.md"), "# Renamed\n").unwrap();
+        assert_eq!(refresh_index(), (Refresh::Updated, 1));
+        let content = fs::read_to_string(temp_dir.join(index)).unwrap();
+        assert!(content.contains("[Renamed](a.md)"));
+
+        let err = refresh(&temp_dir, Path::new("../index.md"), GroupBy::Directory).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
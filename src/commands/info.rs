@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::commands::list::collect_documents;
+use crate::commands::update::{document_metadata, find_document};
+use crate::config::LibraryConfig;
+use crate::date::format_timestamp;
+use crate::frontmatter;
+use crate::links::{self, Destination, Link, LinkGraph};
+use crate::markdown::{self, Block};
+use crate::utils::{self, extract_title_from_content};
+
+/// What a document contains and how it is connected to the rest of the library
+#[derive(Debug, Default)]
+pub struct DocumentInfo {
+    pub path: String,
+    pub title: String,
+    pub size: u64,
+    pub lines: usize,
+    pub words: usize,
+    /// Frontmatter lines, without the `---` delimiters
+    pub frontmatter: Vec<String>,
+    /// Heading levels and texts, in document order
+    pub outline: Vec<(usize, String)>,
+    /// Documents of the library this document links to
+    pub links_to_documents: usize,
+    /// Links to other existing files of the library, such as images and attachments
+    pub links_to_files: usize,
+    pub external_links: usize,
+    /// Links to headings within the document
+    pub anchor_links: usize,
+    /// Documents linking to this one
+    pub inbound: Vec<String>,
+    /// Links to local files that do not exist
+    pub broken: Vec<Link>,
+    /// Completed and total task list items (`- [x]`, `- [ ]`)
+    pub tasks_done: usize,
+    pub tasks: usize,
+    /// Fenced code blocks per language (`""` for blocks without one)
+    pub code_languages: BTreeMap<String, usize>,
+    /// Image targets, in document order
+    pub images: Vec<Link>,
+}
+
+/// Show details about a document
+pub fn run(document: &str) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+
+    let doc_path = find_document(&lib_root, document)?;
+    let lib_canonical = lib_root.canonicalize()?;
+    let relative = doc_path
+        .strip_prefix(&lib_canonical)
+        .unwrap_or(&doc_path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let content = fs::read_to_string(&doc_path)?;
+
+    let documents = collect_documents(&lib_root)?;
+    let graph = LinkGraph::build(&lib_root, &documents, config.walk.threads);
+    let info = document_info(&lib_root, &relative, &content, &graph);
+
+    print_info(&info);
+
+    println!("\nTimestamps:");
+    let metadata = document_metadata(&lib_root, &doc_path, &content);
+    for (label, value) in [
+        ("Created", metadata.created),
+        ("Updated", metadata.updated),
+        ("Authors", metadata.authors),
+    ] {
+        if let Some(value) = value {
+            println!("  {}: {}", label, value);
+        }
+    }
+    let modified = fs::metadata(&doc_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok());
+    if let Some(modified) = modified {
+        println!(
+            "  File modified: {} UTC",
+            format_timestamp(modified.as_secs() as i64)
+        );
+    }
+
+    Ok(())
+}
+
+/// Collect the details of a document at `path` (relative to the library root)
+pub fn document_info(
+    lib_root: &Path,
+    path: &str,
+    content: &str,
+    graph: &LinkGraph,
+) -> DocumentInfo {
+    let lines: Vec<&str> = content.lines().collect();
+    let blocks = markdown::blocks(&lines);
+    let body_start = frontmatter::body_start(&lines);
+
+    let mut info = DocumentInfo {
+        path: path.to_string(),
+        title: extract_title_from_content(content).unwrap_or_else(|| String::from("Untitled")),
+        size: fs::metadata(lib_root.join(path))
+            .map(|m| m.len())
+            .unwrap_or(content.len() as u64),
+        lines: lines.len(),
+        words: markdown::word_count(content),
+        frontmatter: lines[..body_start.saturating_sub(1)]
+            .iter()
+            .skip(1)
+            .map(|line| line.to_string())
+            .collect(),
+        outline: markdown::headings(content)
+            .into_iter()
+            .map(|heading| (heading.level, markdown::plain_text(&heading.text)))
+            .collect(),
+        inbound: graph
+            .inbound
+            .get(path)
+            .map(|paths| paths.iter().cloned().collect())
+            .unwrap_or_default(),
+        broken: graph.broken.get(path).cloned().unwrap_or_default(),
+        ..DocumentInfo::default()
+    };
+
+    for link in links::extract(content) {
+        match links::resolve(path, &link.target) {
+            Destination::External => info.external_links += 1,
+            Destination::Anchor => info.anchor_links += 1,
+            Destination::Local(target) => {
+                if graph
+                    .outbound
+                    .get(path)
+                    .is_some_and(|docs| docs.contains(&target))
+                {
+                    info.links_to_documents += 1;
+                } else if target != path && !is_broken(&info.broken, &link) {
+                    info.links_to_files += 1;
+                }
+            }
+        }
+        if link.image {
+            info.images.push(link);
+        }
+    }
+
+    for (line, block) in lines.iter().zip(&blocks) {
+        if *block != Block::Text {
+            continue;
+        }
+        if let Some(done) = task_state(line) {
+            info.tasks += 1;
+            info.tasks_done += usize::from(done);
+        }
+    }
+
+    for language in markdown::code_block_languages(&lines) {
+        *info
+            .code_languages
+            .entry(language.unwrap_or_default())
+            .or_default() += 1;
+    }
+    info
+}
+
+/// Whether a link is one of the broken links of its document
+fn is_broken(broken: &[Link], link: &Link) -> bool {
+    broken
+        .iter()
+        .any(|other| other.line == link.line && other.target == link.target)
+}
+
+/// Whether a line is a task list item and, if so, whether it is checked
+fn task_state(line: &str) -> Option<bool> {
+    let item = line.trim_start();
+    let rest = if let Some(rest) = item.strip_prefix(['-', '*', '+']) {
+        rest
+    } else {
+        let digits = item.chars().take_while(char::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        item[digits..].strip_prefix(['.', ')'])?
+    };
+    let rest = rest.strip_prefix(' ')?.trim_start();
+    match rest.get(..3)? {
+        "[ ]" => Some(false),
+        "[x]" | "[X]" => Some(true),
+        _ => None,
+    }
+}
+
+/// Print the details of a document
+fn print_info(info: &DocumentInfo) {
+    println!("Document: {}", info.path);
+    println!("  Title: {}", info.title);
+    println!(
+        "  Size: {}, {} lines, {} words",
+        utils::format_size(info.size),
+        info.lines,
+        info.words
+    );
+    println!(
+        "  Reading time: {} min",
+        markdown::reading_minutes(info.words)
+    );
+    if info.tasks > 0 {
+        println!("  Tasks: {} of {} done", info.tasks_done, info.tasks);
+    }
+
+    if !info.frontmatter.is_empty() {
+        println!("\nFrontmatter:");
+        for line in &info.frontmatter {
+            println!("  {}", line);
+        }
+    }
+
+    if !info.outline.is_empty() {
+        println!("\nOutline:");
+        let top = info
+            .outline
+            .iter()
+            .map(|(level, _)| *level)
+            .min()
+            .unwrap_or(1);
+        for (level, text) in &info.outline {
+            println!("  {}{}", "  ".repeat(level - top), text);
+        }
+    }
+
+    println!("\nLinks:");
+    println!(
+        "  Outbound: {} to documents, {} to other files, {} external, {} to headings",
+        info.links_to_documents, info.links_to_files, info.external_links, info.anchor_links
+    );
+    println!("  Inbound: {}", info.inbound.len());
+    for path in &info.inbound {
+        println!("    {}", path);
+    }
+    println!("  Broken: {}", info.broken.len());
+    for link in &info.broken {
+        println!("    line {}: {}", link.line, link.target);
+    }
+
+    if !info.code_languages.is_empty() {
+        let languages: Vec<String> = info
+            .code_languages
+            .iter()
+            .map(|(language, count)| {
+                let language = if language.is_empty() {
+                    "(none)"
+                } else {
+                    language.as_str()
+                };
+                format!("{} ({})", language, count)
+            })
+            .collect();
+        println!("\nCode blocks: {}", languages.join(", "));
+    }
+
+    if !info.images.is_empty() {
+        println!("\nImages:");
+        for image in &info.images {
+            let missing = is_broken(&info.broken, image);
+            println!(
+                "  line {}: {}{}",
+                image.line,
+                image.target,
+                if missing { " (missing)" } else { "" }
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::list::{DocumentEntry, DocumentType};
+    use std::env;
+
+    #[test]
+    fn test_task_state() {
+        assert_eq!(task_state("- [ ] todo"), Some(false));
+        assert_eq!(task_state("  * [x] done"), Some(true));
+        assert_eq!(task_state("3. [X] done"), Some(true));
+        assert_eq!(task_state("- [link](x.md)"), None);
+        assert_eq!(task_state("[ ] not a list item"), None);
+        assert_eq!(task_state("-[ ] no space"), None);
+    }
+
+    #[test]
+    fn test_document_info() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_info");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs/img")).unwrap();
+        fs::write(temp_dir.join("docs/img/a.png"), "").unwrap();
+        let content = "---\nstatus: draft\n---\n# Guide\n\n## Setup\n\nSee [other](other.md), [intro](#guide), [site](https://x.org) and [gone](gone.md).\n\n![a](img/a.png) ![b](img/b.png)\n\n- [x] write\n- [ ] review\n\n```rust\nfn main() {}\n```\n\n```\nplain\n```\n";
+        fs::write(temp_dir.join("docs/guide.md"), content).unwrap();
+        fs::write(
+            temp_dir.join("docs/other.md"),
+            "# Other\n\n[back](guide.md)\n",
+        )
+        .unwrap();
+
+        let documents = vec![
+            DocumentEntry {
+                path: String::from("docs/guide.md"),
+                title: String::from("Guide"),
+                doc_type: DocumentType::Document,
+            },
+            DocumentEntry {
+                path: String::from("docs/other.md"),
+                title: String::from("Other"),
+                doc_type: DocumentType::Document,
+            },
+        ];
+        let graph = LinkGraph::build(&temp_dir, &documents, 1);
+        let info = document_info(&temp_dir, "docs/guide.md", content, &graph);
+
+        assert_eq!(info.title, "Guide");
+        assert_eq!(info.frontmatter, vec!["status: draft"]);
+        assert_eq!(
+            info.outline,
+            vec![(1, String::from("Guide")), (2, String::from("Setup"))]
+        );
+        assert_eq!(info.links_to_documents, 1);
+        // Only the existing image; broken links are counted separately
+        assert_eq!(info.links_to_files, 1);
+        assert_eq!(info.external_links, 1);
+        assert_eq!(info.anchor_links, 1);
+        assert_eq!(info.inbound, vec!["docs/other.md"]);
+        let broken: Vec<&str> = info.broken.iter().map(|l| l.target.as_str()).collect();
+        assert_eq!(broken, vec!["gone.md", "img/b.png"]);
+        assert_eq!((info.tasks_done, info.tasks), (1, 2));
+        assert_eq!(info.code_languages.get("rust"), Some(&1));
+        assert_eq!(info.code_languages.get(""), Some(&1));
+        assert_eq!(info.images.len(), 2);
+        assert_eq!(info.size, content.len() as u64);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -0,0 +1,372 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::commands::edit::launch_editor;
+use crate::config::{LibraryConfig, TEMPLATES_DIR};
+use crate::date::Date;
+use crate::snapshot;
+use crate::storage::{self, LibraryLock};
+use crate::utils::{extract_title_from_content, relative_link, set_marked_block};
+use crate::walker::{DocumentType, Walker};
+
+/// Marker comments surrounding the previous/next navigation links
+const NAV_START: &str = "<!-- journal-nav -->";
+const NAV_END: &str = "<!-- /journal-nav -->";
+
+/// Template used when the library has no journal template
+const DEFAULT_TEMPLATE: &str = r#"# {{date}}
+
+<!-- journal-nav -->
+<!-- /journal-nav -->
+
+## Notes
+
+"#;
+
+/// A dated journal entry
+#[derive(Debug)]
+pub struct JournalEntry {
+    pub date: Date,
+    pub path: PathBuf,
+}
+
+/// Open (creating if needed) the journal entry for a date in the user's editor
+pub fn run(date: Option<&str>) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+
+    let date = parse_date_arg(date)?;
+    let (path, created) = open_entry(&lib_root, &config, date)?;
+
+    if created {
+        println!("Created journal entry: {}", path.display());
+    } else {
+        println!("Journal entry: {}", path.display());
+        snapshot::record(&lib_root, &path)?;
+    }
+
+    launch_editor(&path)
+}
+
+/// List journal entries, optionally only those in the week containing a date
+pub fn run_list(date: Option<&str>, week: bool) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+
+    let date = parse_date_arg(date)?;
+    let entries = collect_entries(&lib_root, &config)?;
+
+    let entries: Vec<&JournalEntry> = if week {
+        let start = date.week_start();
+        let end = start.add_days(7);
+        println!("Week of {} to {}:\n", start, end.add_days(-1));
+        entries
+            .iter()
+            .filter(|e| e.date >= start && e.date < end)
+            .collect()
+    } else {
+        entries.iter().collect()
+    };
+
+    if entries.is_empty() {
+        println!("No journal entries found.");
+        println!("Hint: Run 'mdlibs journal' to create today's entry.");
+        return Ok(());
+    }
+
+    println!("Found {} journal entries:\n", entries.len());
+    println!("{:<12} {:<10} {:>6}  TITLE", "DATE", "DAY", "WORDS");
+    println!("{}", "-".repeat(70));
+
+    for entry in entries {
+        let content = fs::read_to_string(&entry.path)?;
+        let title = extract_title_from_content(&content).unwrap_or_else(|| entry.date.to_string());
+        let words = content.split_whitespace().count();
+        println!(
+            "{:<12} {:<10} {:>6}  {}",
+            entry.date.to_string(),
+            entry.date.weekday(),
+            words,
+            title
+        );
+    }
+
+    Ok(())
+}
+
+/// Parse the `--date` argument, defaulting to today's UTC date
+fn parse_date_arg(date: Option<&str>) -> io::Result<Date> {
+    match date {
+        Some(text) => Date::parse(text).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid date '{}': expected YYYY-MM-DD", text),
+            )
+        }),
+        None => Ok(Date::today()),
+    }
+}
+
+/// The configured journal directory, which must be a relative path inside the library
+fn journal_relative(config: &LibraryConfig) -> io::Result<&Path> {
+    let dir = Path::new(&config.journal.dir);
+    let escapes_library =
+        dir.is_absolute() || dir.components().any(|c| matches!(c, Component::ParentDir));
+    if escapes_library {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "Access denied: journal directory must be inside the library directory",
+        ));
+    }
+    Ok(dir)
+}
+
+/// Path of the entry for a date, e.g. `<journal dir>/2026/10/2026-10-17.md`
+fn entry_path(journal_dir: &Path, date: Date) -> PathBuf {
+    journal_dir
+        .join(format!("{:04}", date.year))
+        .join(format!("{:02}", date.month))
+        .join(format!("{}.md", date))
+}
+
+/// Open the entry for a date, creating it from the template if it does not exist.
+/// Returns the entry path and whether it was created.
+fn open_entry(lib_root: &Path, config: &LibraryConfig, date: Date) -> io::Result<(PathBuf, bool)> {
+    let journal_dir = lib_root.join(journal_relative(config)?);
+    let path = entry_path(&journal_dir, date);
+
+    // Creating an entry also rewrites its neighbours
+    let _lock = LibraryLock::acquire(lib_root)?;
+
+    let created = !path.exists();
+    if created {
+        let template_path = lib_root.join(TEMPLATES_DIR).join(&config.journal.template);
+        let template = if template_path.exists() {
+            fs::read_to_string(&template_path)?
+        } else {
+            DEFAULT_TEMPLATE.to_string()
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        storage::write_atomic(&path, &render_template(&template, date))?;
+    }
+
+    // Refresh navigation links in this entry and its neighbours
+    let entries = collect_entries(lib_root, config)?;
+    if let Some(idx) = entries.iter().position(|e| e.date == date) {
+        let start = idx.saturating_sub(1);
+        let end = (idx + 2).min(entries.len());
+        for i in start..end {
+            // Existing neighbours only gain links when a new entry is created
+            if i == idx || created {
+                refresh_entry_nav(lib_root, &entries, i)?;
+            }
+        }
+    }
+
+    Ok((path, created))
+}
+
+/// Substitute date placeholders in a template
+fn render_template(template: &str, date: Date) -> String {
+    template
+        .replace("{{date}}", &date.to_string())
+        .replace("{{weekday}}", date.weekday())
+        .replace("{{year}}", &format!("{:04}", date.year))
+        .replace("{{month}}", &format!("{:02}", date.month))
+        .replace("{{day}}", &format!("{:02}", date.day))
+}
+
+/// Rewrite the navigation block of `entries[idx]` if it changed
+fn refresh_entry_nav(lib_root: &Path, entries: &[JournalEntry], idx: usize) -> io::Result<()> {
+    let entry = &entries[idx];
+    let prev = idx.checked_sub(1).map(|i| &entries[i]);
+    let next = entries.get(idx + 1);
+
+    let doc = storage::read_document(&entry.path)?;
+    let updated = set_nav(&doc.content, &nav_links(entry, prev, next));
+    if updated != doc.content {
+        storage::write_document(lib_root, &doc, &updated)?;
+    }
+    Ok(())
+}
+
+/// Format the previous/next links for an entry
+fn nav_links(
+    entry: &JournalEntry,
+    prev: Option<&JournalEntry>,
+    next: Option<&JournalEntry>,
+) -> String {
+    let dir = entry.path.parent().unwrap_or_else(|| Path::new(""));
+    let mut links = Vec::new();
+    if let Some(prev) = prev {
+        links.push(format!(
+            "[← {}]({})",
+            prev.date,
+            relative_link(dir, &prev.path)
+        ));
+    }
+    if let Some(next) = next {
+        links.push(format!(
+            "[{} →]({})",
+            next.date,
+            relative_link(dir, &next.path)
+        ));
+    }
+    links.join(" · ")
+}
+
+/// Replace the navigation block, inserting one after the title if the markers are missing
+fn set_nav(content: &str, links: &str) -> String {
+    set_marked_block(content, NAV_START, NAV_END, links)
+}
+
+/// Collect dated entries (files named `YYYY-MM-DD.md`) below the journal directory, sorted by date
+fn collect_entries(lib_root: &Path, config: &LibraryConfig) -> io::Result<Vec<JournalEntry>> {
+    // Entries sit at a fixed depth (year/month) whatever the scan depth of the library
+    let mut entries: Vec<JournalEntry> = Walker::new(lib_root, &config.walk)
+        .max_depth(None)
+        .walk(journal_relative(config)?, DocumentType::Document)?
+        .into_iter()
+        .filter_map(|doc| {
+            let date = doc
+                .path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(Date::parse)?;
+            Some(JournalEntry {
+                date,
+                path: doc.path,
+            })
+        })
+        .collect();
+    entries.sort_by_key(|e| e.date);
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn date(s: &str) -> Date {
+        Date::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_entry_path() {
+        let path = entry_path(Path::new("docs/journal"), date("2026-10-17"));
+        assert_eq!(path, Path::new("docs/journal/2026/10/2026-10-17.md"));
+    }
+
+    #[test]
+    fn test_render_template() {
+        let rendered = render_template("# {{date}} ({{weekday}})\n", date("2026-10-17"));
+        assert_eq!(rendered, "# 2026-10-17 (Saturday)\n");
+    }
+
+    #[test]
+    fn test_set_nav_inserts_after_title() {
+        let result = set_nav("# Day\n\nNotes\n", "[← prev](p.md)");
+        assert_eq!(
+            result,
+            "# Day\n\n<!-- journal-nav -->\n[← prev](p.md)\n<!-- /journal-nav -->\n\nNotes\n"
+        );
+        // Refreshing an existing block replaces it in place
+        assert_eq!(set_nav(&result, "[← prev](p.md)"), result);
+    }
+
+    #[test]
+    fn test_open_entry_links_neighbours() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_journal_open");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        let config = LibraryConfig::default();
+
+        let (first, created) = open_entry(&temp_dir, &config, date("2026-09-30")).unwrap();
+        assert!(created);
+        let (second, _) = open_entry(&temp_dir, &config, date("2026-10-01")).unwrap();
+
+        let first_content = fs::read_to_string(&first).unwrap();
+        assert!(first_content.contains("[2026-10-01 →](../10/2026-10-01.md)"));
+        let second_content = fs::read_to_string(&second).unwrap();
+        assert!(second_content.starts_with("# 2026-10-01"));
+        assert!(second_content.contains("[← 2026-09-30](../09/2026-09-30.md)"));
+
+        // Opening an existing entry does not recreate it
+        let (_, created) = open_entry(&temp_dir, &config, date("2026-09-30")).unwrap();
+        assert!(!created);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_open_entry_uses_template() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_journal_template");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join(TEMPLATES_DIR)).unwrap();
+        fs::write(
+            temp_dir.join(TEMPLATES_DIR).join("daily.md"),
+            "# Log for {{weekday}}\n\n## Done\n",
+        )
+        .unwrap();
+
+        let mut config = LibraryConfig::default();
+        config.journal.dir = String::from("journal");
+        config.journal.template = String::from("daily.md");
+
+        let (path, _) = open_entry(&temp_dir, &config, date("2026-10-17")).unwrap();
+        assert!(path.starts_with(temp_dir.join("journal")));
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# Log for Saturday\n\n<!-- journal-nav -->"));
+        assert!(content.ends_with("## Done\n"));
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_journal_dir_must_stay_inside_library() {
+        let mut config = LibraryConfig::default();
+        for dir in ["../journal", "docs/../../journal", "/tmp/journal"] {
+            config.journal.dir = String::from(dir);
+            let err = journal_relative(&config).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        }
+        config.journal.dir = String::from("notes/journal");
+        assert_eq!(
+            journal_relative(&config).unwrap(),
+            Path::new("notes/journal")
+        );
+    }
+
+    #[test]
+    fn test_collect_entries_ignores_undated_files() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_journal_collect");
+        let _ = fs::remove_dir_all(&temp_dir);
+        let month_dir = temp_dir.join("docs/journal/2026/10");
+        fs::create_dir_all(&month_dir).unwrap();
+        fs::write(month_dir.join("2026-10-17.md"), "# a").unwrap();
+        fs::write(month_dir.join("2026-10-02.md"), "# b").unwrap();
+        fs::write(month_dir.join("notes.md"), "# c").unwrap();
+
+        let entries = collect_entries(&temp_dir, &LibraryConfig::default()).unwrap();
+        let dates: Vec<String> = entries.iter().map(|e| e.date.to_string()).collect();
+        assert_eq!(dates, vec!["2026-10-02", "2026-10-17"]);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
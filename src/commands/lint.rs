@@ -0,0 +1,83 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::commands::list::collect_documents;
+use crate::commands::update::find_document;
+use crate::config::LibraryConfig;
+use crate::lint::{self, lint};
+use crate::storage::{self, LibraryLock};
+
+/// Check documents (all documents in the library if none are given) against the lint
+/// rules configured in `.mdlibs.toml`, first fixing what can be fixed if `fix` is set
+pub fn run(documents: &[String], fix: bool) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+    let lib_canonical = lib_root.canonicalize()?;
+
+    let paths: Vec<PathBuf> = if documents.is_empty() {
+        collect_documents(&lib_root)?
+            .into_iter()
+            .map(|doc| lib_canonical.join(doc.path))
+            .collect()
+    } else {
+        documents
+            .iter()
+            .map(|document| find_document(&lib_root, document))
+            .collect::<io::Result<_>>()?
+    };
+
+    let _lock = if fix {
+        Some(LibraryLock::acquire(&lib_root)?)
+    } else {
+        None
+    };
+
+    let mut problems = 0;
+    let mut documents_with_problems = 0;
+    let mut fixed = 0;
+    for path in &paths {
+        let relative = path.strip_prefix(&lib_canonical).unwrap_or(path);
+        let doc = storage::read_document(path)?;
+        let mut diagnostics = lint(&doc.content, &config.lint);
+
+        if fix && !diagnostics.is_empty() {
+            let fixed_content = lint::fix(&doc.content, &config.lint);
+            if fixed_content != doc.content {
+                storage::write_document(&lib_root, &doc, &fixed_content)?;
+                let remaining = lint(&fixed_content, &config.lint);
+                let count = diagnostics.len().saturating_sub(remaining.len());
+                println!("Fixed {} problem(s) in {}", count, relative.display());
+                fixed += count;
+                diagnostics = remaining;
+            }
+        }
+        if diagnostics.is_empty() {
+            continue;
+        }
+
+        for diagnostic in &diagnostics {
+            println!("{}:{}", relative.display(), diagnostic);
+        }
+        problems += diagnostics.len();
+        documents_with_problems += 1;
+    }
+
+    if problems > 0 {
+        return Err(io::Error::other(format!(
+            "Found {} problem(s) in {} document(s)",
+            problems, documents_with_problems
+        )));
+    }
+
+    if fixed > 0 {
+        println!("Fixed {} problem(s); no problems left.", fixed);
+    } else {
+        println!("No problems found in {} document(s).", paths.len());
+    }
+    Ok(())
+}
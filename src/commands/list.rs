@@ -1,9 +1,19 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io;
 use std::path::Path;
+use std::time::UNIX_EPOCH;
 
-use crate::config::{LibraryConfig, DOCS_DIR, TEMPLATES_DIR};
-use crate::utils::{extract_title_from_file, is_markdown_file};
+use crate::config::LibraryConfig;
+use crate::date::Date;
+use crate::frontmatter;
+use crate::git;
+use crate::markdown;
+use crate::parallel;
+use crate::utils::{self, extract_title_from_file};
+use crate::walker::{DocumentHandle, Walker};
+
+pub use crate::walker::DocumentType;
 
 /// Document entry representing a markdown file
 #[derive(Debug)]
@@ -13,24 +23,188 @@ pub struct DocumentEntry {
     pub doc_type: DocumentType,
 }
 
-/// Type of document
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub enum DocumentType {
-    Document,
-    Template,
+/// Order in which documents are listed
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Alphabetically by title
+    Title,
+    /// Alphabetically by path (the default)
+    Path,
+    /// Most recently modified first (last commit time in git libraries, else file mtime)
+    Modified,
+    /// Most recently created first (`created` frontmatter, else first commit or file time)
+    Created,
+    /// Largest file first
+    Size,
+    /// Most words first
+    Words,
+}
+
+/// A column of the document listing
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Type,
+    Title,
+    Path,
+    Modified,
+    Created,
+    Size,
+    Words,
+    /// The value of a frontmatter key
+    Frontmatter(String),
 }
 
-impl std::fmt::Display for DocumentType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// Columns shown when `--columns` is not given
+const DEFAULT_COLUMNS: [Column; 3] = [Column::Type, Column::Title, Column::Path];
+
+/// Narrowest a column is truncated to when fitting the listing to the terminal
+const MIN_COLUMN_WIDTH: usize = 10;
+
+impl Column {
+    /// Heading of the column
+    fn header(&self) -> String {
         match self {
-            DocumentType::Document => write!(f, "doc"),
-            DocumentType::Template => write!(f, "template"),
+            Column::Type => String::from("TYPE"),
+            Column::Title => String::from("TITLE"),
+            Column::Path => String::from("PATH"),
+            Column::Modified => String::from("MODIFIED"),
+            Column::Created => String::from("CREATED"),
+            Column::Size => String::from("SIZE"),
+            Column::Words => String::from("WORDS"),
+            Column::Frontmatter(key) => key.to_uppercase(),
+        }
+    }
+
+    /// Whether the column holds numbers, which are aligned right
+    fn is_numeric(&self) -> bool {
+        matches!(self, Column::Size | Column::Words)
+    }
+
+    /// Whether long values may be cut to fit the terminal
+    fn is_truncatable(&self) -> bool {
+        matches!(self, Column::Title | Column::Path | Column::Frontmatter(_))
+    }
+}
+
+/// Parse a `--columns` entry; names other than the built-in columns are frontmatter keys
+pub fn parse_column(name: &str) -> Result<Column, String> {
+    let name = name.trim();
+    let column = match name.to_lowercase().as_str() {
+        "type" => Column::Type,
+        "title" => Column::Title,
+        "path" => Column::Path,
+        "modified" => Column::Modified,
+        "created" => Column::Created,
+        "size" => Column::Size,
+        "words" => Column::Words,
+        _ if name.is_empty() || name.contains(|c: char| c == ':' || c.is_whitespace()) => {
+            return Err(format!("invalid column '{}'", name));
+        }
+        _ => Column::Frontmatter(name.to_string()),
+    };
+    Ok(column)
+}
+
+/// Which documents to list and how
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Only list documents whose title or path contains this text
+    pub filter: Option<String>,
+    /// Order of the documents (path order if None)
+    pub sort: Option<SortKey>,
+    pub reverse: bool,
+    /// Skip this many documents of the sorted listing
+    pub offset: usize,
+    /// Show at most this many documents
+    pub limit: Option<usize>,
+    /// Columns to show (the default columns if empty)
+    pub columns: Vec<Column>,
+    /// Show the documents as a directory tree instead of a table
+    pub tree: bool,
+    /// Directory levels to expand in the tree (all if None)
+    pub depth: Option<usize>,
+}
+
+/// A directory in the tree view: the documents directly inside it, in listing order,
+/// and its subdirectories
+#[derive(Debug, Default)]
+struct TreeNode<'a> {
+    dirs: BTreeMap<String, TreeNode<'a>>,
+    docs: Vec<&'a DocumentEntry>,
+    /// Documents in this directory and all directories below it
+    count: usize,
+}
+
+impl<'a> TreeNode<'a> {
+    /// Build the tree of a list of documents, keeping their order within each directory
+    fn build(documents: &[&'a DocumentEntry]) -> Self {
+        let mut root = Self::default();
+        for doc in documents {
+            let mut node = &mut root;
+            node.count += 1;
+            let mut components: Vec<&str> = doc.path.split('/').collect();
+            components.pop();
+            for dir in components {
+                node = node.dirs.entry(dir.to_string()).or_default();
+                node.count += 1;
+            }
+            node.docs.push(doc);
         }
+        root
+    }
+
+    /// Render the entries of this directory as lines of the tree, expanding
+    /// subdirectories while `depth` levels remain
+    fn render(&self, prefix: &str, depth: Option<usize>, lines: &mut Vec<String>) {
+        let entries = self.dirs.len() + self.docs.len();
+        let mut index = 0;
+        for (name, dir) in &self.dirs {
+            index += 1;
+            let (branch, indent) = tree_branch(index == entries);
+            lines.push(format!("{}{}{}/ ({})", prefix, branch, name, dir.count));
+            let remaining = depth.map(|depth| depth.saturating_sub(1));
+            if remaining != Some(0) {
+                dir.render(&format!("{}{}", prefix, indent), remaining, lines);
+            }
+        }
+        for doc in &self.docs {
+            index += 1;
+            let (branch, _) = tree_branch(index == entries);
+            let name = doc.path.rsplit('/').next().unwrap_or(&doc.path);
+            let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+            if doc.title == stem {
+                lines.push(format!("{}{}{}", prefix, branch, name));
+            } else {
+                lines.push(format!("{}{}{} - {}", prefix, branch, name, doc.title));
+            }
+        }
+    }
+}
+
+/// The branch drawn before a tree entry and the indent for the entries below it
+fn tree_branch(last: bool) -> (&'static str, &'static str) {
+    if last {
+        ("└── ", "    ")
+    } else {
+        ("├── ", "│   ")
     }
 }
 
+/// Values of a document read from disk and git, used for sorting and extra columns
+#[derive(Debug, Default)]
+pub struct DocumentFacts {
+    /// Last modification as a Unix timestamp
+    pub modified: i64,
+    /// Creation as a Unix timestamp
+    pub created: i64,
+    /// File size in bytes
+    pub size: u64,
+    pub words: usize,
+    pub content: String,
+}
+
 /// List markdown documents in the library
-pub fn run(filter: Option<&str>) -> io::Result<()> {
+pub fn run(options: &ListOptions) -> io::Result<()> {
     let current_dir = std::env::current_dir()?;
 
     // Try to find library root
@@ -46,7 +220,7 @@ pub fn run(filter: Option<&str>) -> io::Result<()> {
     }
 
     // Apply filter if provided
-    let filtered_docs: Vec<&DocumentEntry> = if let Some(filter_text) = filter {
+    let filtered_docs: Vec<&DocumentEntry> = if let Some(filter_text) = &options.filter {
         let filter_lower = filter_text.to_lowercase();
         documents
             .iter()
@@ -60,119 +234,283 @@ pub fn run(filter: Option<&str>) -> io::Result<()> {
     };
 
     if filtered_docs.is_empty() {
-        println!("No documents match filter: {}", filter.unwrap_or(""));
+        println!(
+            "No documents match filter: {}",
+            options.filter.as_deref().unwrap_or("")
+        );
         return Ok(());
     }
 
-    println!("Found {} document(s):\n", filtered_docs.len());
-    println!("{:<10} {:<40} PATH", "TYPE", "TITLE");
-    println!("{}", "-".repeat(70));
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+    let columns: &[Column] = if options.columns.is_empty() {
+        &DEFAULT_COLUMNS
+    } else {
+        &options.columns
+    };
+    let needs_facts = matches!(
+        options.sort,
+        Some(SortKey::Modified | SortKey::Created | SortKey::Size | SortKey::Words)
+    ) || columns
+        .iter()
+        .any(|column| !matches!(column, Column::Type | Column::Title | Column::Path));
+    let facts = if needs_facts {
+        read_facts(&lib_root, &filtered_docs, config.walk.threads)
+    } else {
+        filtered_docs
+            .iter()
+            .map(|_| DocumentFacts::default())
+            .collect()
+    };
+
+    let mut rows: Vec<(&DocumentEntry, DocumentFacts)> =
+        filtered_docs.into_iter().zip(facts).collect();
+    sort_rows(&mut rows, options.sort, options.reverse);
+
+    if options.tree {
+        let sorted: Vec<&DocumentEntry> = rows.iter().map(|(doc, _)| *doc).collect();
+        let tree = TreeNode::build(&sorted);
+        let mut lines = vec![format!("{} ({} documents)", config.name, tree.count)];
+        tree.render("", options.depth, &mut lines);
+        let width = utils::terminal_width();
+        for line in lines {
+            match width {
+                Some(width) => println!("{}", utils::truncate_to_width(&line, width)),
+                None => println!("{}", line),
+            }
+        }
+        return Ok(());
+    }
 
-    for doc in filtered_docs {
-        println!("{:<10} {:<40} {}", doc.doc_type, doc.title, doc.path);
+    let total = rows.len();
+    let page: Vec<_> = rows
+        .iter()
+        .skip(options.offset)
+        .take(options.limit.unwrap_or(usize::MAX))
+        .collect();
+    if page.is_empty() {
+        println!(
+            "Found {} document(s), none after offset {}.",
+            total, options.offset
+        );
+        return Ok(());
+    }
+    if page.len() < total {
+        println!(
+            "Found {} document(s), showing {}-{}:\n",
+            total,
+            options.offset + 1,
+            options.offset + page.len()
+        );
+    } else {
+        println!("Found {} document(s):\n", total);
     }
 
+    let cells: Vec<Vec<String>> = page
+        .iter()
+        .map(|(doc, facts)| {
+            columns
+                .iter()
+                .map(|column| cell(column, doc, facts))
+                .collect()
+        })
+        .collect();
+    print_table(columns, &cells, utils::terminal_width());
+
     Ok(())
 }
 
-/// Collect all markdown documents from the library
-fn collect_documents(lib_root: &Path) -> io::Result<Vec<DocumentEntry>> {
-    let mut documents = Vec::new();
+/// Read the modification and creation times, size, word count and content of each
+/// document, in the order of the documents
+pub fn read_facts(
+    lib_root: &Path,
+    documents: &[&DocumentEntry],
+    threads: usize,
+) -> Vec<DocumentFacts> {
+    let commit_times = if git::is_repository(lib_root) {
+        git::commit_times(lib_root).unwrap_or_default()
+    } else {
+        git::CommitTimes::default()
+    };
 
-    // Scan docs directory
-    let docs_path = lib_root.join(DOCS_DIR);
-    if docs_path.exists() {
-        scan_directory(&docs_path, lib_root, DocumentType::Document, &mut documents)?;
+    let threads = parallel::thread_count(threads);
+    parallel::map(documents, threads, |doc| {
+        let path = lib_root.join(&doc.path);
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        DocumentFacts {
+            modified: modified_time(lib_root, &doc.path, &commit_times.last),
+            created: created_time(lib_root, &doc.path, &content, &commit_times.first),
+            size: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            words: markdown::word_count(&content),
+            content,
+        }
+    })
+}
+
+/// Sort rows by a key, keeping path order between equal rows. Names sort A to Z; times,
+/// sizes and word counts largest first. `reverse` flips the order.
+fn sort_rows(rows: &mut [(&DocumentEntry, DocumentFacts)], sort: Option<SortKey>, reverse: bool) {
+    // Rows arrive in walk order (docs, templates, then the root), so sort by path first
+    // and let the stable sorts below keep it between equal rows
+    rows.sort_by(|(a, _), (b, _)| a.path.cmp(&b.path));
+    match sort.unwrap_or(SortKey::Path) {
+        SortKey::Title => rows.sort_by_cached_key(|(doc, _)| doc.title.to_lowercase()),
+        SortKey::Path => {}
+        SortKey::Modified => rows.sort_by_key(|(_, facts)| std::cmp::Reverse(facts.modified)),
+        SortKey::Created => rows.sort_by_key(|(_, facts)| std::cmp::Reverse(facts.created)),
+        SortKey::Size => rows.sort_by_key(|(_, facts)| std::cmp::Reverse(facts.size)),
+        SortKey::Words => rows.sort_by_key(|(_, facts)| std::cmp::Reverse(facts.words)),
+    }
+    if reverse {
+        rows.reverse();
     }
+}
 
-    // Scan templates directory
-    let templates_path = lib_root.join(TEMPLATES_DIR);
-    if templates_path.exists() {
-        scan_directory(
-            &templates_path,
-            lib_root,
-            DocumentType::Template,
-            &mut documents,
-        )?;
+/// The text of a column for a document
+fn cell(column: &Column, doc: &DocumentEntry, facts: &DocumentFacts) -> String {
+    match column {
+        Column::Type => doc.doc_type.to_string(),
+        Column::Title => doc.title.clone(),
+        Column::Path => doc.path.clone(),
+        Column::Modified => Date::from_unix_timestamp(facts.modified).to_string(),
+        Column::Created => Date::from_unix_timestamp(facts.created).to_string(),
+        Column::Size => utils::format_size(facts.size),
+        Column::Words => facts.words.to_string(),
+        // Lists (`tags: [a, b]` or `- a` lines) are joined; other values are shown as written
+        Column::Frontmatter(key) => frontmatter::get_list(&facts.content, key)
+            .map(|items| items.join(", "))
+            .or_else(|| frontmatter::get(&facts.content, key))
+            .unwrap_or_default(),
     }
+}
 
-    // Also scan root for any markdown files
-    scan_single_directory(lib_root, lib_root, DocumentType::Document, &mut documents)?;
+/// Print rows under a header, padding columns to their widest value and truncating
+/// text columns so lines fit the terminal width when there is one
+fn print_table(columns: &[Column], cells: &[Vec<String>], width: Option<usize>) {
+    let headers: Vec<String> = columns.iter().map(Column::header).collect();
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in cells {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+    if let Some(available) = width {
+        fit_widths(&mut widths, columns, available);
+    }
 
-    Ok(documents)
+    println!("{}", format_row(columns, &widths, &headers));
+    let total: usize = widths.iter().sum::<usize>() + widths.len().saturating_sub(1);
+    println!("{}", "-".repeat(total));
+    for row in cells {
+        println!("{}", format_row(columns, &widths, row));
+    }
 }
 
-/// Scan a directory recursively for markdown files
-fn scan_directory(
-    dir: &Path,
-    lib_root: &Path,
-    doc_type: DocumentType,
-    documents: &mut Vec<DocumentEntry>,
-) -> io::Result<()> {
-    if !dir.is_dir() {
-        return Ok(());
+/// Narrow the widest truncatable columns until the row fits in `available` characters
+/// or no column can be narrowed further
+fn fit_widths(widths: &mut [usize], columns: &[Column], available: usize) {
+    let separators = widths.len().saturating_sub(1);
+    let mut total: usize = widths.iter().sum::<usize>() + separators;
+    while total > available {
+        let widest = widths
+            .iter()
+            .enumerate()
+            .filter(|(i, &width)| columns[*i].is_truncatable() && width > MIN_COLUMN_WIDTH)
+            .max_by_key(|(_, &width)| width)
+            .map(|(i, _)| i);
+        let Some(widest) = widest else {
+            break;
+        };
+        widths[widest] -= 1;
+        total -= 1;
     }
+}
 
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_dir() {
-            scan_directory(&path, lib_root, doc_type, documents)?;
-        } else if is_markdown_file(&path) {
-            if let Some(doc_entry) = create_document_entry(&path, lib_root, doc_type) {
-                documents.push(doc_entry);
+/// Format one line of the table; the last column is not padded
+fn format_row(columns: &[Column], widths: &[usize], values: &[String]) -> String {
+    let last = columns.len() - 1;
+    let fields: Vec<String> = columns
+        .iter()
+        .zip(widths)
+        .zip(values)
+        .enumerate()
+        .map(|(i, ((column, &width), value))| {
+            let value = utils::truncate_to_width(value, width);
+            if column.is_numeric() {
+                format!("{:>width$}", value, width = width)
+            } else if i == last {
+                value
+            } else {
+                format!("{:<width$}", value, width = width)
             }
-        }
-    }
+        })
+        .collect();
+    fields.join(" ")
+}
 
-    Ok(())
+/// Last modification time of a document as a Unix timestamp, preferring the time of
+/// its last commit and falling back to the file's mtime for uncommitted documents
+fn modified_time(lib_root: &Path, path: &str, commit_times: &HashMap<String, i64>) -> i64 {
+    if let Some(&time) = commit_times.get(path) {
+        return time;
+    }
+    fs::metadata(lib_root.join(path))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
 }
 
-/// Scan only a single directory (non-recursive) for markdown files
-fn scan_single_directory(
-    dir: &Path,
+/// Creation time of a document as a Unix timestamp: its `created` frontmatter date,
+/// else the time of its first commit, else the file's creation (or modification) time
+fn created_time(
     lib_root: &Path,
-    doc_type: DocumentType,
-    documents: &mut Vec<DocumentEntry>,
-) -> io::Result<()> {
-    if !dir.is_dir() {
-        return Ok(());
+    path: &str,
+    content: &str,
+    first_commit_times: &HashMap<String, i64>,
+) -> i64 {
+    let frontmatter_date = frontmatter::get(content, "created")
+        .and_then(|value| value.get(..10).and_then(Date::parse));
+    if let Some(date) = frontmatter_date {
+        return date.to_days() * 86_400;
     }
-
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_file() && is_markdown_file(&path) {
-            if let Some(doc_entry) = create_document_entry(&path, lib_root, doc_type) {
-                documents.push(doc_entry);
-            }
-        }
+    if let Some(&time) = first_commit_times.get(path) {
+        return time;
     }
+    fs::metadata(lib_root.join(path))
+        .and_then(|m| m.created().or_else(|_| m.modified()))
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
 
-    Ok(())
+/// Collect all markdown documents from the library, sorted by path within the docs
+/// directory, the templates directory and the library root
+pub fn collect_documents(lib_root: &Path) -> io::Result<Vec<DocumentEntry>> {
+    let config = LibraryConfig::load_or_default(lib_root)?;
+    let documents = Walker::new(lib_root, &config.walk).documents()?;
+
+    // Reading titles opens every file, so it is spread over threads too
+    let threads = parallel::thread_count(config.walk.threads);
+    Ok(parallel::map(&documents, threads, create_document_entry))
 }
 
-/// Create a document entry from a file path
-fn create_document_entry(
-    path: &Path,
-    lib_root: &Path,
-    doc_type: DocumentType,
-) -> Option<DocumentEntry> {
-    let relative_path = path.strip_prefix(lib_root).ok()?;
-    let title = extract_title_from_file(path).unwrap_or_else(|| {
-        path.file_stem()
+/// Create a document entry from a document found by the walker
+fn create_document_entry(doc: &DocumentHandle) -> DocumentEntry {
+    let title = extract_title_from_file(&doc.path).unwrap_or_else(|| {
+        doc.path
+            .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("Untitled")
             .to_string()
     });
 
-    Some(DocumentEntry {
-        path: relative_path.to_string_lossy().to_string(),
+    DocumentEntry {
+        path: doc.relative.clone(),
         title,
-        doc_type,
-    })
+        doc_type: doc.doc_type,
+    }
 }
 
 #[cfg(test)]
@@ -184,6 +522,172 @@ mod tests {
     fn test_document_type_display() {
         assert_eq!(format!("{}", DocumentType::Document), "doc");
         assert_eq!(format!("{}", DocumentType::Template), "template");
+        assert_eq!(format!("{:<5}|", DocumentType::Document), "doc  |");
+    }
+
+    fn entry(path: &str, title: &str) -> DocumentEntry {
+        DocumentEntry {
+            path: path.to_string(),
+            title: title.to_string(),
+            doc_type: DocumentType::Document,
+        }
+    }
+
+    #[test]
+    fn test_parse_column() {
+        assert_eq!(parse_column("Title"), Ok(Column::Title));
+        assert_eq!(parse_column("words"), Ok(Column::Words));
+        assert_eq!(
+            parse_column("status"),
+            Ok(Column::Frontmatter(String::from("status")))
+        );
+        assert!(parse_column("").is_err());
+        assert!(parse_column("a key").is_err());
+    }
+
+    #[test]
+    fn test_sort_rows() {
+        // In walk order: docs, templates, then the library root
+        let docs = [
+            entry("docs/b.md", "alpha"),
+            entry("templates/t.md", "Beta"),
+            entry("a.md", "gamma"),
+        ];
+        let facts = |size, words| DocumentFacts {
+            size,
+            words,
+            ..DocumentFacts::default()
+        };
+        let paths = |rows: &[(&DocumentEntry, DocumentFacts)]| -> Vec<String> {
+            rows.iter().map(|(doc, _)| doc.path.clone()).collect()
+        };
+        let rows = || {
+            vec![
+                (&docs[0], facts(10, 5)),
+                (&docs[1], facts(30, 5)),
+                (&docs[2], facts(20, 5)),
+            ]
+        };
+
+        let mut sorted = rows();
+        sort_rows(&mut sorted, None, false);
+        assert_eq!(paths(&sorted), vec!["a.md", "docs/b.md", "templates/t.md"]);
+        let mut sorted = rows();
+        sort_rows(&mut sorted, Some(SortKey::Title), false);
+        assert_eq!(paths(&sorted), vec!["docs/b.md", "templates/t.md", "a.md"]);
+        let mut sorted = rows();
+        sort_rows(&mut sorted, Some(SortKey::Size), false);
+        assert_eq!(paths(&sorted), vec!["templates/t.md", "a.md", "docs/b.md"]);
+        let mut sorted = rows();
+        sort_rows(&mut sorted, Some(SortKey::Size), true);
+        assert_eq!(paths(&sorted), vec!["docs/b.md", "a.md", "templates/t.md"]);
+        // Ties keep path order
+        let mut sorted = rows();
+        sort_rows(&mut sorted, Some(SortKey::Words), false);
+        assert_eq!(paths(&sorted), vec!["a.md", "docs/b.md", "templates/t.md"]);
+    }
+
+    #[test]
+    fn test_frontmatter_cell() {
+        let doc = entry("a.md", "A");
+        let facts = DocumentFacts {
+            content: String::from(
+                "---\nstatus: draft\ntags: [rust, cli]\nauthors:\n  - ann\n  - bob\n---\n# A\n",
+            ),
+            ..DocumentFacts::default()
+        };
+        let column = |key: &str| Column::Frontmatter(String::from(key));
+        assert_eq!(cell(&column("status"), &doc, &facts), "draft");
+        assert_eq!(cell(&column("tags"), &doc, &facts), "rust, cli");
+        assert_eq!(cell(&column("authors"), &doc, &facts), "ann, bob");
+        assert_eq!(cell(&column("missing"), &doc, &facts), "");
+    }
+
+    #[test]
+    fn test_fit_widths_and_format_row() {
+        let columns = [Column::Type, Column::Title, Column::Path, Column::Words];
+        let mut widths = vec![8, 40, 30, 5];
+        fit_widths(&mut widths, &columns, 60);
+        assert_eq!(widths.iter().sum::<usize>() + 3, 60);
+        assert_eq!(&widths[..1], &[8]);
+        assert_eq!(widths[3], 5);
+        assert!(widths[1] >= MIN_COLUMN_WIDTH && widths[2] >= MIN_COLUMN_WIDTH);
+
+        // Columns are never narrowed below the minimum, even if the row stays too wide
+        let mut widths = vec![8, 12, 12, 5];
+        fit_widths(&mut widths, &columns, 20);
+        assert_eq!(widths, vec![8, MIN_COLUMN_WIDTH, MIN_COLUMN_WIDTH, 5]);
+
+        let values = ["doc", "A long title here", "docs/a.md", "42"].map(String::from);
+        assert_eq!(
+            format_row(&columns, &[4, 10, 10, 5], &values),
+            "doc  A long ... docs/a.md     42"
+        );
+    }
+
+    #[test]
+    fn test_tree() {
+        let docs = [
+            entry("README.md", "Readme"),
+            entry("docs/guide.md", "User Guide"),
+            entry("docs/notes/a.md", "a"),
+            entry("docs/notes/b.md", "Bee"),
+            entry("templates/note.md", "Note"),
+        ];
+        let refs: Vec<&DocumentEntry> = docs.iter().collect();
+        let tree = TreeNode::build(&refs);
+        assert_eq!(tree.count, 5);
+        assert_eq!(tree.dirs["docs"].count, 3);
+
+        let mut lines = Vec::new();
+        tree.render("", None, &mut lines);
+        assert_eq!(
+            lines,
+            vec![
+                "├── docs/ (3)",
+                "│   ├── notes/ (2)",
+                "│   │   ├── a.md",
+                "│   │   └── b.md - Bee",
+                "│   └── guide.md - User Guide",
+                "├── templates/ (1)",
+                "│   └── note.md - Note",
+                "└── README.md - Readme",
+            ]
+        );
+
+        let mut lines = Vec::new();
+        tree.render("", Some(1), &mut lines);
+        assert_eq!(
+            lines,
+            vec![
+                "├── docs/ (3)",
+                "├── templates/ (1)",
+                "└── README.md - Readme"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_created_time_prefers_frontmatter() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_list_created");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("a.md"), "# A").unwrap();
+
+        let content = "---\ncreated: 2024-03-01\n---\n# A\n";
+        let first_commit_times = HashMap::from([(String::from("a.md"), 42)]);
+        assert_eq!(
+            created_time(&temp_dir, "a.md", content, &first_commit_times),
+            Date::new(2024, 3, 1).unwrap().to_days() * 86_400
+        );
+        assert_eq!(
+            created_time(&temp_dir, "a.md", "# A", &first_commit_times),
+            42
+        );
+        assert!(created_time(&temp_dir, "a.md", "# A", &HashMap::new()) > 42);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
     }
 
     #[test]
@@ -222,6 +726,23 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_modified_time_prefers_commit_time() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_list_modified");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("a.md"), "# A").unwrap();
+        fs::write(temp_dir.join("b.md"), "# B").unwrap();
+
+        let commit_times = HashMap::from([(String::from("a.md"), 42)]);
+        assert_eq!(modified_time(&temp_dir, "a.md", &commit_times), 42);
+        // Uncommitted files fall back to their mtime
+        assert!(modified_time(&temp_dir, "b.md", &commit_times) > 42);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     fn test_create_document_entry() {
         let temp_dir = env::temp_dir().join("mdlibs_test_list_entry");
@@ -231,9 +752,8 @@ mod tests {
         let test_file = temp_dir.join("test.md");
         fs::write(&test_file, "# Test Title\n\nContent").unwrap();
 
-        let entry = create_document_entry(&test_file, &temp_dir, DocumentType::Document);
-        assert!(entry.is_some());
-        let entry = entry.unwrap();
+        let handle = DocumentHandle::new(&temp_dir, &temp_dir, test_file, DocumentType::Document);
+        let entry = create_document_entry(&handle);
         assert_eq!(entry.title, "Test Title");
         assert_eq!(entry.path, "test.md");
         assert_eq!(entry.doc_type, DocumentType::Document);
@@ -251,9 +771,8 @@ mod tests {
         let test_file = temp_dir.join("notitle.md");
         fs::write(&test_file, "Content without title").unwrap();
 
-        let entry = create_document_entry(&test_file, &temp_dir, DocumentType::Document);
-        assert!(entry.is_some());
-        let entry = entry.unwrap();
+        let handle = DocumentHandle::new(&temp_dir, &temp_dir, test_file, DocumentType::Document);
+        let entry = create_document_entry(&handle);
         // Should use filename as title when no H1 heading exists
         assert_eq!(entry.title, "notitle");
 
@@ -1,4 +1,15 @@
+pub mod edit;
+pub mod fmt;
+pub mod history;
+pub mod index;
+pub mod info;
 pub mod init;
+pub mod journal;
+pub mod lint;
 pub mod list;
+pub mod orphans;
 pub mod search;
+pub mod stats;
+pub mod toc;
 pub mod update;
+pub mod versions;
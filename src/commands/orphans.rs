@@ -0,0 +1,110 @@
+use std::io;
+
+use crate::commands::list::{collect_documents, DocumentEntry};
+use crate::config::LibraryConfig;
+use crate::links::LinkGraph;
+
+/// Which part of the report to show
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ReportPart {
+    /// Documents with no inbound links
+    Orphans,
+    /// Documents with no outbound links to other documents
+    DeadEnds,
+}
+
+/// Report documents that are not linked to, or that link nowhere
+pub fn run(only: Option<ReportPart>) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+
+    let documents = collect_documents(&lib_root)?;
+    if documents.is_empty() {
+        println!("No markdown documents found.");
+        return Ok(());
+    }
+    let graph = LinkGraph::build(&lib_root, &documents, config.walk.threads);
+
+    if only != Some(ReportPart::DeadEnds) {
+        let orphans = graph.orphans(&documents, &config.links.entry_points);
+        print_section("Orphans (no other document links to them)", &orphans);
+        if !orphans.is_empty() && !config.links.entry_points.is_empty() {
+            println!(
+                "  Entry points not reported: {}",
+                config.links.entry_points.join(", ")
+            );
+        }
+    }
+    if only.is_none() {
+        println!();
+    }
+    if only != Some(ReportPart::Orphans) {
+        let dead_ends = graph.dead_ends(&documents);
+        print_section("Dead ends (they link to no other document)", &dead_ends);
+    }
+
+    Ok(())
+}
+
+/// Print a heading with the number of documents, then one document per line
+fn print_section(heading: &str, documents: &[&DocumentEntry]) {
+    println!("{}: {}", heading, documents.len());
+    let width = documents
+        .iter()
+        .map(|doc| doc.path.chars().count())
+        .max()
+        .unwrap_or(0);
+    for doc in documents {
+        println!("  {:<width$}  {}", doc.path, doc.title, width = width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_orphans_and_dead_ends_in_library() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_orphans");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs/notes")).unwrap();
+        fs::create_dir_all(temp_dir.join("templates")).unwrap();
+        fs::write(
+            temp_dir.join("docs/README.md"),
+            "# Home\n\n[Linked](notes/linked.md)\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.join("docs/notes/linked.md"),
+            "# Linked\n\n[Home](../README.md)\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.join("docs/notes/lonely.md"), "# Lonely\n").unwrap();
+        fs::write(temp_dir.join("templates/note.md"), "# Note\n").unwrap();
+
+        let config = LibraryConfig::default();
+        let documents = collect_documents(&temp_dir).unwrap();
+        let graph = LinkGraph::build(&temp_dir, &documents, 1);
+        let paths = |docs: Vec<&DocumentEntry>| -> Vec<String> {
+            docs.iter().map(|doc| doc.path.clone()).collect()
+        };
+        // The README is an entry point and templates are never reported
+        assert_eq!(
+            paths(graph.orphans(&documents, &config.links.entry_points)),
+            vec!["docs/notes/lonely.md"]
+        );
+        assert_eq!(
+            paths(graph.dead_ends(&documents)),
+            vec!["docs/notes/lonely.md"]
+        );
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
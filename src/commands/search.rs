@@ -1,9 +1,71 @@
 use std::fs;
-use std::io;
+use std::io::{self, IsTerminal};
+use std::ops::Range;
 use std::path::Path;
 
-use crate::config::{LibraryConfig, DOCS_DIR, TEMPLATES_DIR};
-use crate::utils::{extract_title_from_content, is_markdown_file, truncate_display};
+use clap::ValueEnum;
+
+use crate::config::LibraryConfig;
+use crate::markdown::{self, Block, Inline, Outline};
+use crate::parallel;
+use crate::utils::{extract_title_from_content, truncate_display};
+use crate::walker::{DocumentHandle, Walker};
+
+/// Width of the snippet shown for each matching or context line
+const SNIPPET_WIDTH: usize = 60;
+
+/// ANSI escapes used to highlight matches
+const HIGHLIGHT_START: &str = "\x1b[1;31m";
+const HIGHLIGHT_END: &str = "\x1b[0m";
+
+/// When to highlight matches with ANSI colors
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ColorChoice {
+    /// When stdout is a terminal and NO_COLOR is not set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether output should be colored
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+        }
+    }
+}
+
+/// Part of the markdown structure to restrict a search to
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Scope {
+    /// Fenced code blocks and code spans
+    Code,
+    /// Body text, including link text, outside code, headings, URLs and comments
+    Prose,
+    /// Heading text
+    Headings,
+    /// Link text, link targets and URLs
+    Links,
+}
+
+/// What to search and how much surrounding text to keep
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    pub title_only: bool,
+    /// Only match within this part of each document (the whole text if None)
+    pub scope: Option<Scope>,
+    /// Only match within fenced code blocks of this language
+    pub lang: Option<String>,
+    /// Lines of context to keep before each match
+    pub before: usize,
+    /// Lines of context to keep after each match
+    pub after: usize,
+}
 
 /// Search result entry
 #[derive(Debug)]
@@ -18,17 +80,34 @@ pub struct SearchResult {
 pub struct SearchMatch {
     pub line_number: usize,
     pub line_content: String,
+    /// Headings enclosing the match, outermost first (e.g. `Setup > Linux`)
+    pub heading_path: Vec<String>,
+    /// Anchor of the section containing the match, for linking to it
+    pub anchor: Option<String>,
+    /// Byte range of the first occurrence of the query in `line_content`
+    pub span: Range<usize>,
+    /// Lines preceding the match, up to the requested context
+    pub before: Vec<String>,
+    /// Lines following the match, up to the requested context
+    pub after: Vec<String>,
 }
 
 /// Search through markdown documents
-pub fn run(query: &str, title_only: bool) -> io::Result<()> {
+pub fn run(query: &str, options: &SearchOptions, color: ColorChoice) -> io::Result<()> {
     let current_dir = std::env::current_dir()?;
 
     // Try to find library root
     let lib_root =
         LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
 
-    let results = search_documents(&lib_root, query, title_only)?;
+    if options.lang.is_some() && !matches!(options.scope, None | Some(Scope::Code)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--lang searches code blocks and can only be combined with --in code",
+        ));
+    }
+
+    let results = search_documents(&lib_root, query, options)?;
 
     if results.is_empty() {
         println!("No results found for: {}", query);
@@ -37,13 +116,12 @@ pub fn run(query: &str, title_only: bool) -> io::Result<()> {
 
     println!("Found {} result(s) for '{}':\n", results.len(), query);
 
+    let highlight = color.enabled();
+    let query_lower = query.to_lowercase();
     for result in results {
         println!("📄 {} ({})", result.title, result.path);
-        if !title_only {
-            for match_entry in &result.matches {
-                let preview = truncate_display(&match_entry.line_content, 60);
-                println!("   Line {}: {}", match_entry.line_number, preview);
-            }
+        if !options.title_only {
+            print_matches(&result, &query_lower, highlight);
         }
         println!();
     }
@@ -51,92 +129,249 @@ pub fn run(query: &str, title_only: bool) -> io::Result<()> {
     Ok(())
 }
 
-/// Search documents in the library
-fn search_documents(
-    lib_root: &Path,
-    query: &str,
-    title_only: bool,
-) -> io::Result<Vec<SearchResult>> {
-    let mut results = Vec::new();
-    let query_lower = query.to_lowercase();
+/// Print the matches of a result with their context, like grep: context lines are
+/// marked with `-`, overlapping context is printed once and separate groups are
+/// divided by `--`
+fn print_matches(result: &SearchResult, query: &str, highlight: bool) {
+    let mut last_printed = 0;
+    for (index, match_entry) in result.matches.iter().enumerate() {
+        let first = match_entry.line_number - match_entry.before.len();
+        let has_context = !match_entry.before.is_empty() || !match_entry.after.is_empty();
+        if has_context && last_printed > 0 && first > last_printed + 1 {
+            println!("   --");
+        }
 
-    // Collect all markdown files
-    let mut files = Vec::new();
-    collect_markdown_files(&lib_root.join(DOCS_DIR), &mut files)?;
-    collect_markdown_files(&lib_root.join(TEMPLATES_DIR), &mut files)?;
-    collect_markdown_files_single(lib_root, &mut files)?;
+        for (offset, line) in match_entry.before.iter().enumerate() {
+            let line_number = first + offset;
+            if line_number > last_printed {
+                println!(
+                    "   Line {}- {}",
+                    line_number,
+                    truncate_display(line, SNIPPET_WIDTH)
+                );
+            }
+        }
 
-    for file_path in files {
-        if let Some(result) = search_file(&file_path, lib_root, &query_lower, title_only)? {
-            results.push(result);
+        let preview = snippet(
+            &match_entry.line_content,
+            match_entry.span.clone(),
+            SNIPPET_WIDTH,
+        );
+        let preview = if highlight {
+            highlight_matches(&preview, query)
+        } else {
+            preview
+        };
+        println!("   Line {}: {}", match_entry.line_number, preview);
+        if let Some(anchor) = &match_entry.anchor {
+            println!(
+                "      in {} ({}#{})",
+                match_entry.heading_path.join(" > "),
+                result.path,
+                anchor
+            );
+        }
+        last_printed = match_entry.line_number;
+
+        // Stop before the next match, which prints its own line
+        let next_match = result
+            .matches
+            .get(index + 1)
+            .map(|next| next.line_number)
+            .unwrap_or(usize::MAX);
+        for (offset, line) in match_entry.after.iter().enumerate() {
+            let line_number = match_entry.line_number + 1 + offset;
+            if line_number >= next_match {
+                break;
+            }
+            println!(
+                "   Line {}- {}",
+                line_number,
+                truncate_display(line, SNIPPET_WIDTH)
+            );
+            last_printed = line_number;
         }
     }
-
-    Ok(results)
 }
 
-/// Collect markdown files from a directory recursively
-fn collect_markdown_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> io::Result<()> {
-    if !dir.exists() || !dir.is_dir() {
-        return Ok(());
+/// Byte ranges of the case-insensitive, non-overlapping occurrences of `query_lower`
+/// (already lowercased) in `line`
+fn find_matches(line: &str, query_lower: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    if query_lower.is_empty() {
+        return ranges;
     }
 
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
+    let mut start = 0;
+    while let Some(c) = line[start..].chars().next() {
+        match match_len_at(&line[start..], query_lower) {
+            Some(len) => {
+                ranges.push(start..start + len);
+                start += len;
+            }
+            None => start += c.len_utf8(),
+        }
+    }
+    ranges
+}
 
-        if path.is_dir() {
-            collect_markdown_files(&path, files)?;
-        } else if is_markdown_file(&path) {
-            files.push(path);
+/// Length in bytes of the prefix of `text` that lowercases to `query_lower`
+fn match_len_at(text: &str, query_lower: &str) -> Option<usize> {
+    let mut lowered = String::new();
+    for (index, c) in text.char_indices() {
+        lowered.extend(c.to_lowercase());
+        if lowered.len() >= query_lower.len() {
+            return (lowered == query_lower).then_some(index + c.len_utf8());
+        }
+        if !query_lower.starts_with(&lowered) {
+            return None;
         }
     }
+    None
+}
 
-    Ok(())
+/// The byte ranges of each line that are searched under the given scope, with `lang`
+/// restricting it to fenced code blocks of that language. Frontmatter, fences and
+/// HTML comments are never in scope.
+fn scope_ranges(lines: &[&str], scope: Scope, lang: Option<&str>) -> Vec<Vec<Range<usize>>> {
+    let mut in_comment = false;
+    markdown::blocks(lines)
+        .into_iter()
+        .zip(lines)
+        .map(|(block, line)| match block {
+            Block::Code(block_lang) => {
+                let wanted = scope == Scope::Code
+                    && lang.map_or(true, |lang| {
+                        block_lang.is_some_and(|block_lang| block_lang.eq_ignore_ascii_case(lang))
+                    });
+                if wanted {
+                    std::iter::once(0..line.len()).collect()
+                } else {
+                    Vec::new()
+                }
+            }
+            Block::Frontmatter | Block::Fence => Vec::new(),
+            Block::Heading | Block::Text => {
+                let heading = block == Block::Heading;
+                let mut ranges: Vec<Range<usize>> = Vec::new();
+                for span in markdown::inline_spans(line, &mut in_comment) {
+                    let wanted = match scope {
+                        Scope::Code => lang.is_none() && span.kind == Inline::Code,
+                        Scope::Prose => {
+                            !heading && matches!(span.kind, Inline::Text | Inline::LinkText)
+                        }
+                        Scope::Headings => heading && span.kind != Inline::Comment,
+                        Scope::Links => matches!(
+                            span.kind,
+                            Inline::LinkText | Inline::LinkTarget | Inline::Url
+                        ),
+                    };
+                    if !wanted {
+                        continue;
+                    }
+                    // Adjacent spans merge so a match may cross from text into code
+                    match ranges.last_mut() {
+                        Some(last) if last.end == span.range.start => last.end = span.range.end,
+                        _ => ranges.push(span.range),
+                    }
+                }
+                ranges
+            }
+        })
+        .collect()
 }
 
-/// Collect markdown files from a single directory (non-recursive)
-fn collect_markdown_files_single(
-    dir: &Path,
-    files: &mut Vec<std::path::PathBuf>,
-) -> io::Result<()> {
-    if !dir.exists() || !dir.is_dir() {
-        return Ok(());
+/// Cut a window of at most `width` characters out of a trimmed line, centered on the
+/// matched byte range, marking cut ends with `...`
+fn snippet(line: &str, span: Range<usize>, width: usize) -> String {
+    let leading = line.len() - line.trim_start().len();
+    let trimmed = line.trim();
+    let chars: Vec<(usize, char)> = trimmed.char_indices().collect();
+    if chars.len() <= width {
+        return trimmed.to_string();
+    }
+
+    // Convert the byte range to character positions within the trimmed line
+    let span_start = span.start.saturating_sub(leading);
+    let span_end = span.end.saturating_sub(leading);
+    let match_start = chars.partition_point(|(byte, _)| *byte < span_start);
+    let match_end = chars.partition_point(|(byte, _)| *byte < span_end);
+    let match_len = match_end - match_start;
+
+    let start = if match_len >= width {
+        match_start
+    } else {
+        match_start.saturating_sub((width - match_len) / 2)
+    };
+    let end = (start + width).min(chars.len());
+    let start = end.saturating_sub(width);
+
+    let window: String = chars[start..end].iter().map(|(_, c)| c).collect();
+    format!(
+        "{}{}{}",
+        if start > 0 { "..." } else { "" },
+        window,
+        if end < chars.len() { "..." } else { "" }
+    )
+}
+
+/// Wrap each occurrence of the query in ANSI highlight escapes
+fn highlight_matches(text: &str, query_lower: &str) -> String {
+    let mut highlighted = String::new();
+    let mut last = 0;
+    for range in find_matches(text, query_lower) {
+        highlighted.push_str(&text[last..range.start]);
+        highlighted.push_str(HIGHLIGHT_START);
+        highlighted.push_str(&text[range.clone()]);
+        highlighted.push_str(HIGHLIGHT_END);
+        last = range.end;
     }
+    highlighted.push_str(&text[last..]);
+    highlighted
+}
 
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
+/// Search documents in the library
+fn search_documents(
+    lib_root: &Path,
+    query: &str,
+    options: &SearchOptions,
+) -> io::Result<Vec<SearchResult>> {
+    let mut results = Vec::new();
+    let query_lower = query.to_lowercase();
+    let config = LibraryConfig::load_or_default(lib_root)?;
+    let threads = parallel::thread_count(config.walk.threads);
 
-        if path.is_file() && is_markdown_file(&path) {
-            files.push(path);
+    let documents = Walker::new(lib_root, &config.walk).documents()?;
+
+    // Documents are searched in parallel; results keep the order of `documents`
+    for result in parallel::map(&documents, threads, |doc| {
+        search_file(doc, &query_lower, options)
+    }) {
+        if let Some(result) = result? {
+            results.push(result);
         }
     }
 
-    Ok(())
+    Ok(results)
 }
 
 /// Search within a single file
 fn search_file(
-    path: &Path,
-    lib_root: &Path,
+    doc: &DocumentHandle,
     query: &str,
-    title_only: bool,
+    options: &SearchOptions,
 ) -> io::Result<Option<SearchResult>> {
-    let content = fs::read_to_string(path)?;
+    let content = fs::read_to_string(&doc.path)?;
     let title = extract_title_from_content(&content).unwrap_or_else(|| {
-        path.file_stem()
+        doc.path
+            .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("Untitled")
             .to_string()
     });
+    let relative_path = doc.relative.clone();
 
-    let relative_path = path
-        .strip_prefix(lib_root)
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| path.to_string_lossy().to_string());
-
-    if title_only {
+    if options.title_only {
         // Only search in title
         if title.to_lowercase().contains(query) {
             return Ok(Some(SearchResult {
@@ -148,12 +383,45 @@ fn search_file(
     } else {
         // Search in full content
         let mut matches = Vec::new();
-
-        for (line_num, line) in content.lines().enumerate() {
-            if line.to_lowercase().contains(query) {
+        // Headings are only parsed for documents that match
+        let mut outline = None;
+
+        let lines: Vec<&str> = content.lines().collect();
+        let scopes = (options.scope.is_some() || options.lang.is_some()).then(|| {
+            scope_ranges(
+                &lines,
+                options.scope.unwrap_or(Scope::Code),
+                options.lang.as_deref(),
+            )
+        });
+
+        for (line_num, line) in lines.iter().enumerate() {
+            let in_scope = |span: &Range<usize>| {
+                scopes.as_ref().map_or(true, |scopes| {
+                    scopes[line_num]
+                        .iter()
+                        .any(|range| range.start <= span.start && span.end <= range.end)
+                })
+            };
+            if let Some(span) = find_matches(line, query).into_iter().find(in_scope) {
+                let section = outline
+                    .get_or_insert_with(|| Outline::new(&content))
+                    .section_at(line_num);
+                let after_end = (line_num + 1 + options.after).min(lines.len());
                 matches.push(SearchMatch {
                     line_number: line_num + 1,
                     line_content: line.to_string(),
+                    heading_path: section.map(|s| s.path.clone()).unwrap_or_default(),
+                    anchor: section.map(|s| s.anchor.clone()),
+                    span,
+                    before: lines[line_num.saturating_sub(options.before)..line_num]
+                        .iter()
+                        .map(|l| l.to_string())
+                        .collect(),
+                    after: lines[line_num + 1..after_end]
+                        .iter()
+                        .map(|l| l.to_string())
+                        .collect(),
                 });
             }
         }
@@ -173,7 +441,13 @@ fn search_file(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::walker::DocumentType;
     use std::env;
+    use std::path::PathBuf;
+
+    fn handle(lib_root: &Path, path: PathBuf) -> DocumentHandle {
+        DocumentHandle::new(lib_root, lib_root, path, DocumentType::Document)
+    }
 
     #[test]
     fn test_search_file() {
@@ -188,7 +462,12 @@ mod tests {
         )
         .unwrap();
 
-        let result = search_file(&test_file, &temp_dir, "rust", false).unwrap();
+        let result = search_file(
+            &handle(&temp_dir, test_file.clone()),
+            "rust",
+            &SearchOptions::default(),
+        )
+        .unwrap();
         assert!(result.is_some());
         let result = result.unwrap();
         assert_eq!(result.title, "Test Document");
@@ -198,6 +477,37 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_search_match_heading_path() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_search_sections");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let test_file = temp_dir.join("test.md");
+        fs::write(
+            &test_file,
+            "# Guide\n\n## Setup\n\n### Linux Dependencies\n\nInstall rust first.\n",
+        )
+        .unwrap();
+
+        let result = search_file(
+            &handle(&temp_dir, test_file.clone()),
+            "rust",
+            &SearchOptions::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let found = &result.matches[0];
+        assert_eq!(
+            found.heading_path,
+            vec!["Guide", "Setup", "Linux Dependencies"]
+        );
+        assert_eq!(found.anchor.as_deref(), Some("linux-dependencies"));
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     fn test_search_title_only() {
         let temp_dir = env::temp_dir().join("mdlibs_test_search_title");
@@ -206,16 +516,146 @@ mod tests {
 
         let test_file = temp_dir.join("test.md");
         fs::write(&test_file, "# Rust Guide\n\nThis is about programming.").unwrap();
+        let title_only = SearchOptions {
+            title_only: true,
+            ..SearchOptions::default()
+        };
 
         // Should match title
-        let result = search_file(&test_file, &temp_dir, "rust", true).unwrap();
+        let result =
+            search_file(&handle(&temp_dir, test_file.clone()), "rust", &title_only).unwrap();
         assert!(result.is_some());
 
         // Should not match - query not in title
-        let result = search_file(&test_file, &temp_dir, "programming", true).unwrap();
+        let result = search_file(
+            &handle(&temp_dir, test_file.clone()),
+            "programming",
+            &title_only,
+        )
+        .unwrap();
         assert!(result.is_none());
 
         // Cleanup
         let _ = fs::remove_dir_all(&temp_dir);
     }
+
+    #[test]
+    fn test_search_context_lines() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_search_context");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let test_file = temp_dir.join("test.md");
+        fs::write(&test_file, "# Doc\n\none\ntwo RUST\nthree\n").unwrap();
+        let options = SearchOptions {
+            before: 2,
+            after: 5,
+            ..SearchOptions::default()
+        };
+
+        let result = search_file(&handle(&temp_dir, test_file.clone()), "rust", &options)
+            .unwrap()
+            .unwrap();
+        let found = &result.matches[0];
+        assert_eq!(found.line_number, 4);
+        assert_eq!(found.span, 4..8);
+        assert_eq!(found.before, vec!["", "one"]);
+        assert_eq!(found.after, vec!["three"]);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_find_matches_case_insensitive() {
+        assert_eq!(find_matches("Rust and rust", "rust"), vec![0..4, 9..13]);
+        assert_eq!(find_matches("ÄÖ äö", "äö"), vec![0..4, 5..9]);
+        assert!(find_matches("nothing", "rust").is_empty());
+    }
+
+    #[test]
+    fn test_snippet_centers_on_match() {
+        let line = format!("{}needle{}", "a".repeat(50), "b".repeat(50));
+        let span = 50..56;
+        let cut = snippet(&line, span, 20);
+        assert_eq!(
+            cut,
+            format!("...{}needle{}...", "a".repeat(7), "b".repeat(7))
+        );
+
+        // Matches near either end keep the window inside the line
+        assert_eq!(snippet(&line, 0..1, 10), format!("{}...", "a".repeat(10)));
+        assert_eq!(
+            snippet(&line, 105..106, 10),
+            format!("...{}", "b".repeat(10))
+        );
+        assert_eq!(snippet("  short needle  ", 8..14, 20), "short needle");
+    }
+
+    #[test]
+    fn test_highlight_matches() {
+        assert_eq!(
+            highlight_matches("a Rust b", "rust"),
+            format!("a {}Rust{} b", HIGHLIGHT_START, HIGHLIGHT_END)
+        );
+    }
+
+    fn scoped_lines(content: &str, scope: Scope, lang: Option<&str>, query: &str) -> Vec<usize> {
+        let temp_dir =
+            env::temp_dir().join(format!("mdlibs_test_search_scope_{:?}_{:?}", scope, lang));
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        let test_file = temp_dir.join("test.md");
+        fs::write(&test_file, content).unwrap();
+
+        let options = SearchOptions {
+            scope: Some(scope),
+            lang: lang.map(String::from),
+            ..SearchOptions::default()
+        };
+        let lines = search_file(&handle(&temp_dir, test_file.clone()), query, &options)
+            .unwrap()
+            .map(|result| result.matches.iter().map(|m| m.line_number).collect())
+            .unwrap_or_default();
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+        lines
+    }
+
+    const SCOPED: &str = "---\ntags: [cargo]\n---\n# Cargo guide\n\nUse cargo to build.\n<!-- cargo note -->\nRun `cargo test`, see [cargo docs](https://doc.rust-lang.org/cargo).\n\n```rust\n// cargo\n```\n\n```sh\ncargo build\n```\n";
+
+    #[test]
+    fn test_search_scopes() {
+        assert_eq!(
+            scoped_lines(SCOPED, Scope::Code, None, "cargo"),
+            vec![8, 11, 15]
+        );
+        assert_eq!(
+            scoped_lines(SCOPED, Scope::Prose, None, "cargo"),
+            vec![6, 8]
+        );
+        assert_eq!(
+            scoped_lines(SCOPED, Scope::Headings, None, "cargo"),
+            vec![4]
+        );
+        assert_eq!(scoped_lines(SCOPED, Scope::Links, None, "cargo"), vec![8]);
+        // Only the URL mentions rust-lang
+        assert_eq!(
+            scoped_lines(SCOPED, Scope::Prose, None, "rust-lang"),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn test_search_code_language() {
+        assert_eq!(
+            scoped_lines(SCOPED, Scope::Code, Some("sh"), "cargo"),
+            vec![15]
+        );
+        assert_eq!(
+            scoped_lines(SCOPED, Scope::Code, Some("RUST"), "cargo"),
+            vec![11]
+        );
+    }
 }
@@ -0,0 +1,477 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::Path;
+
+use crate::commands::list::{collect_documents, read_facts, DocumentEntry, DocumentFacts};
+use crate::config::{LibraryConfig, DOCS_DIR};
+use crate::date::Date;
+use crate::frontmatter;
+use crate::git;
+use crate::json::Json;
+use crate::links::{self, LinkGraph};
+use crate::markdown;
+use crate::walker::DocumentType;
+
+/// Documents shown in each ranking and tags shown in the text report
+const TOP_COUNT: usize = 5;
+
+/// Months of growth shown in the text report
+const GROWTH_MONTHS: usize = 12;
+
+/// A document in one of the rankings, with the value it was ranked by
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedDocument {
+    pub path: String,
+    pub title: String,
+    /// Word count or Unix timestamp, depending on the ranking
+    pub value: i64,
+}
+
+/// Documents added in a month, from git history
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthGrowth {
+    /// `YYYY-MM`
+    pub month: String,
+    pub added: usize,
+    /// Documents added up to the end of the month
+    pub total: usize,
+}
+
+/// Library-wide statistics
+#[derive(Debug, Default)]
+pub struct LibraryStats {
+    pub documents: usize,
+    pub templates: usize,
+    /// Documents and templates per top-level folder
+    pub by_folder: BTreeMap<String, usize>,
+    /// Documents per tag, most used first
+    pub by_tag: Vec<(String, usize)>,
+    pub total_words: usize,
+    pub reading_minutes: usize,
+    /// Documents with the most words
+    pub largest: Vec<RankedDocument>,
+    /// Documents created longest ago
+    pub oldest: Vec<RankedDocument>,
+    /// Documents modified longest ago
+    pub stalest: Vec<RankedDocument>,
+    pub orphans: usize,
+    pub dead_ends: usize,
+    pub broken_links: usize,
+    /// Documents added per month, None outside git libraries
+    pub growth: Option<Vec<MonthGrowth>>,
+}
+
+impl LibraryStats {
+    /// Average words per document
+    pub fn average_words(&self) -> usize {
+        self.total_words.checked_div(self.documents).unwrap_or(0)
+    }
+}
+
+/// Show statistics about the library
+pub fn run(json: bool) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+    let config = LibraryConfig::load_or_default(&lib_root)?;
+
+    let documents = collect_documents(&lib_root)?;
+    let stats = collect_stats(&lib_root, &config, &documents);
+
+    if json {
+        println!("{}", stats_json(&stats).pretty());
+    } else {
+        print_stats(&config.name, &stats);
+    }
+    Ok(())
+}
+
+/// Compute the statistics of a library. Counts by folder include templates; word counts,
+/// rankings, link figures and growth cover documents only.
+pub fn collect_stats(
+    lib_root: &Path,
+    config: &LibraryConfig,
+    documents: &[DocumentEntry],
+) -> LibraryStats {
+    let refs: Vec<&DocumentEntry> = documents.iter().collect();
+    let facts = read_facts(lib_root, &refs, config.walk.threads);
+
+    let mut stats = LibraryStats::default();
+    let mut tags: HashMap<String, usize> = HashMap::new();
+    let mut ranked: Vec<(&DocumentEntry, &DocumentFacts)> = Vec::new();
+    for (doc, facts) in documents.iter().zip(&facts) {
+        *stats.by_folder.entry(top_folder(&doc.path)).or_default() += 1;
+        if doc.doc_type == DocumentType::Template {
+            stats.templates += 1;
+            continue;
+        }
+        stats.documents += 1;
+        stats.total_words += facts.words;
+        for tag in frontmatter::get_list(&facts.content, "tags").unwrap_or_default() {
+            *tags.entry(tag).or_default() += 1;
+        }
+        ranked.push((doc, facts));
+    }
+    stats.reading_minutes = markdown::reading_minutes(stats.total_words);
+
+    let mut by_tag: Vec<(String, usize)> = tags.into_iter().collect();
+    by_tag.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    stats.by_tag = by_tag;
+
+    stats.largest = top_documents(
+        &ranked,
+        |facts| -(facts.words as i64),
+        |facts| facts.words as i64,
+    );
+    stats.oldest = top_documents(&ranked, |facts| facts.created, |facts| facts.created);
+    stats.stalest = top_documents(&ranked, |facts| facts.modified, |facts| facts.modified);
+
+    let links: Vec<Vec<links::Link>> = facts
+        .iter()
+        .map(|facts| links::extract(&facts.content))
+        .collect();
+    let graph = LinkGraph::from_links(lib_root, documents, &links);
+    stats.orphans = graph.orphans(documents, &config.links.entry_points).len();
+    stats.dead_ends = graph.dead_ends(documents).len();
+    stats.broken_links = documents
+        .iter()
+        .filter(|doc| doc.doc_type == DocumentType::Document)
+        .filter_map(|doc| graph.broken.get(&doc.path))
+        .map(Vec::len)
+        .sum();
+
+    if git::is_repository(lib_root) {
+        let first_commits = git::commit_times(lib_root).unwrap_or_default().first;
+        let added: Vec<i64> = ranked
+            .iter()
+            .filter_map(|(doc, _)| first_commits.get(&doc.path).copied())
+            .collect();
+        stats.growth = Some(monthly_growth(&added));
+    }
+    stats
+}
+
+/// The folder a document is counted under: the first folder below `docs` (or the first
+/// folder of the path outside it), `.` for the library root
+fn top_folder(path: &str) -> String {
+    let dirs: Vec<&str> = path.split('/').collect();
+    let dirs = &dirs[..dirs.len() - 1];
+    match dirs {
+        [] => String::from("."),
+        [first, second, ..] if *first == DOCS_DIR => format!("{}/{}", first, second),
+        [first, ..] => first.to_string(),
+    }
+}
+
+/// The first documents by ascending `key`, in path order between equal keys, each with
+/// the value shown for it
+fn top_documents(
+    documents: &[(&DocumentEntry, &DocumentFacts)],
+    key: impl Fn(&DocumentFacts) -> i64,
+    value: impl Fn(&DocumentFacts) -> i64,
+) -> Vec<RankedDocument> {
+    let mut sorted = documents.to_vec();
+    sorted.sort_by_key(|(doc, facts)| (key(facts), doc.path.clone()));
+    sorted
+        .into_iter()
+        .take(TOP_COUNT)
+        .map(|(doc, facts)| RankedDocument {
+            path: doc.path.clone(),
+            title: doc.title.clone(),
+            value: value(facts),
+        })
+        .collect()
+}
+
+/// Documents added per month, with the running total, from the times documents were
+/// first committed
+fn monthly_growth(added: &[i64]) -> Vec<MonthGrowth> {
+    let mut months: BTreeMap<String, usize> = BTreeMap::new();
+    for &time in added {
+        let date = Date::from_unix_timestamp(time);
+        *months
+            .entry(format!("{:04}-{:02}", date.year, date.month))
+            .or_default() += 1;
+    }
+    let mut total = 0;
+    months
+        .into_iter()
+        .map(|(month, added)| {
+            total += added;
+            MonthGrowth {
+                month,
+                added,
+                total,
+            }
+        })
+        .collect()
+}
+
+/// Print the statistics as a text report
+fn print_stats(name: &str, stats: &LibraryStats) {
+    println!("Library: {}\n", name);
+    println!(
+        "Documents: {}, templates: {}",
+        stats.documents, stats.templates
+    );
+    println!(
+        "Words: {} total, {} per document on average",
+        stats.total_words,
+        stats.average_words()
+    );
+    println!(
+        "Reading time: {} min total, {} min per document on average",
+        stats.reading_minutes,
+        markdown::reading_minutes(stats.average_words())
+    );
+    println!(
+        "Links: {} orphan(s), {} dead end(s), {} broken link(s)",
+        stats.orphans, stats.dead_ends, stats.broken_links
+    );
+
+    println!("\nBy folder:");
+    let width = stats
+        .by_folder
+        .keys()
+        .map(|k| k.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (folder, count) in &stats.by_folder {
+        println!("  {:<width$}  {}", folder, count, width = width);
+    }
+
+    if !stats.by_tag.is_empty() {
+        println!("\nTop tags:");
+        let tags = &stats.by_tag[..stats.by_tag.len().min(TOP_COUNT)];
+        let width = tags
+            .iter()
+            .map(|(t, _)| t.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (tag, count) in tags {
+            println!("  {:<width$}  {}", tag, count, width = width);
+        }
+    }
+
+    print_ranking("Largest documents", &stats.largest, |words| {
+        format!("{} words", words)
+    });
+    print_ranking("Oldest documents", &stats.oldest, format_date);
+    print_ranking("Least recently modified", &stats.stalest, format_date);
+
+    if let Some(growth) = &stats.growth {
+        if !growth.is_empty() {
+            println!("\nDocuments added per month:");
+            for month in &growth[growth.len().saturating_sub(GROWTH_MONTHS)..] {
+                println!(
+                    "  {}  +{:<4} total {}",
+                    month.month, month.added, month.total
+                );
+            }
+        }
+    }
+}
+
+/// Print a ranking of documents under a heading, each with its formatted value
+fn print_ranking(heading: &str, documents: &[RankedDocument], format: fn(i64) -> String) {
+    if documents.is_empty() {
+        return;
+    }
+    println!("\n{}:", heading);
+    let values: Vec<String> = documents.iter().map(|doc| format(doc.value)).collect();
+    let width = values.iter().map(|v| v.len()).max().unwrap_or(0);
+    for (doc, value) in documents.iter().zip(&values) {
+        println!(
+            "  {:>width$}  {} ({})",
+            value,
+            doc.title,
+            doc.path,
+            width = width
+        );
+    }
+}
+
+/// A Unix timestamp as a `YYYY-MM-DD` date
+fn format_date(timestamp: i64) -> String {
+    Date::from_unix_timestamp(timestamp).to_string()
+}
+
+/// The statistics as a JSON document
+fn stats_json(stats: &LibraryStats) -> Json {
+    let ranking = |documents: &[RankedDocument], key: &str, date: bool| {
+        Json::Array(
+            documents
+                .iter()
+                .map(|doc| {
+                    let value = if date {
+                        Json::from(format_date(doc.value))
+                    } else {
+                        Json::from(doc.value)
+                    };
+                    Json::object([
+                        ("path", Json::from(doc.path.as_str())),
+                        ("title", Json::from(doc.title.as_str())),
+                        (key, value),
+                    ])
+                })
+                .collect(),
+        )
+    };
+    let growth = stats.growth.as_ref().map(|growth| {
+        Json::Array(
+            growth
+                .iter()
+                .map(|month| {
+                    Json::object([
+                        ("month", Json::from(month.month.as_str())),
+                        ("added", Json::from(month.added)),
+                        ("total", Json::from(month.total)),
+                    ])
+                })
+                .collect(),
+        )
+    });
+
+    Json::object([
+        ("documents", Json::from(stats.documents)),
+        ("templates", Json::from(stats.templates)),
+        (
+            "by_folder",
+            Json::object(
+                stats
+                    .by_folder
+                    .iter()
+                    .map(|(folder, count)| (folder.as_str(), Json::from(*count))),
+            ),
+        ),
+        (
+            "by_tag",
+            Json::object(
+                stats
+                    .by_tag
+                    .iter()
+                    .map(|(tag, count)| (tag.as_str(), Json::from(*count))),
+            ),
+        ),
+        (
+            "words",
+            Json::object([
+                ("total", Json::from(stats.total_words)),
+                ("average", Json::from(stats.average_words())),
+            ]),
+        ),
+        (
+            "reading_minutes",
+            Json::object([
+                ("total", Json::from(stats.reading_minutes)),
+                (
+                    "average",
+                    Json::from(markdown::reading_minutes(stats.average_words())),
+                ),
+            ]),
+        ),
+        ("largest", ranking(&stats.largest, "words", false)),
+        ("oldest", ranking(&stats.oldest, "created", true)),
+        ("stalest", ranking(&stats.stalest, "modified", true)),
+        (
+            "links",
+            Json::object([
+                ("orphans", Json::from(stats.orphans)),
+                ("dead_ends", Json::from(stats.dead_ends)),
+                ("broken", Json::from(stats.broken_links)),
+            ]),
+        ),
+        ("growth", Json::from(growth)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_top_folder() {
+        assert_eq!(top_folder("README.md"), ".");
+        assert_eq!(top_folder("docs/a.md"), "docs");
+        assert_eq!(top_folder("docs/projects/x/a.md"), "docs/projects");
+        assert_eq!(top_folder("templates/note.md"), "templates");
+    }
+
+    #[test]
+    fn test_monthly_growth() {
+        let day = 86_400;
+        let jan = Date::new(2026, 1, 10).unwrap().to_days() * day;
+        let mar = Date::new(2026, 3, 2).unwrap().to_days() * day;
+        let growth = monthly_growth(&[mar, jan, jan + day]);
+        assert_eq!(
+            growth,
+            vec![
+                MonthGrowth {
+                    month: String::from("2026-01"),
+                    added: 2,
+                    total: 2
+                },
+                MonthGrowth {
+                    month: String::from("2026-03"),
+                    added: 1,
+                    total: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_stats() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_stats");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs/projects")).unwrap();
+        fs::create_dir_all(temp_dir.join("templates")).unwrap();
+        fs::write(
+            temp_dir.join("docs/README.md"),
+            "# Home\n\nSee [alpha](projects/alpha.md).\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.join("docs/projects/alpha.md"),
+            "---\ntags: [rust, cli]\ncreated: 2020-01-01\n---\n# Alpha\n\none two three four five\n[gone](gone.md)\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.join("docs/beta.md"),
+            "---\ntags: [rust]\n---\n# Beta\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.join("templates/note.md"), "# Note\n").unwrap();
+
+        let config = LibraryConfig::default();
+        let documents = collect_documents(&temp_dir).unwrap();
+        let stats = collect_stats(&temp_dir, &config, &documents);
+        assert_eq!(stats.documents, 3);
+        assert_eq!(stats.templates, 1);
+        assert_eq!(stats.by_folder["docs"], 2);
+        assert_eq!(stats.by_folder["docs/projects"], 1);
+        assert_eq!(stats.by_folder["templates"], 1);
+        assert_eq!(
+            stats.by_tag,
+            vec![(String::from("rust"), 2), (String::from("cli"), 1)]
+        );
+        assert_eq!(stats.largest[0].path, "docs/projects/alpha.md");
+        assert_eq!(stats.oldest[0].path, "docs/projects/alpha.md");
+        // beta is not linked to; README is an entry point
+        assert_eq!(stats.orphans, 1);
+        // Neither alpha (only a broken link) nor beta links to another document
+        assert_eq!(stats.dead_ends, 2);
+        assert_eq!(stats.broken_links, 1);
+
+        let json = stats_json(&stats).pretty();
+        assert!(json.contains("\"documents\": 3"));
+        assert!(json.contains("\"created\": \"2020-01-01\""));
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -0,0 +1,120 @@
+use std::io;
+
+use crate::commands::update::find_document;
+use crate::config::LibraryConfig;
+use crate::markdown::{headings, plain_text, Slugger};
+use crate::storage;
+use crate::utils::set_marked_block;
+
+/// Marker comments surrounding the table of contents
+const TOC_START: &str = "<!-- toc -->";
+const TOC_END: &str = "<!-- /toc -->";
+
+/// Insert or refresh the table of contents of a document, or with `check`, only
+/// report whether it is up to date
+pub fn run(document: &str, depth: usize, check: bool) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    let doc_path = find_document(&lib_root, document)?;
+    let doc = storage::read_document(&doc_path)?;
+    let updated = set_toc(&doc.content, &render_toc(&doc.content, depth));
+
+    if updated == doc.content {
+        println!("Table of contents is up to date: {}", doc_path.display());
+        return Ok(());
+    }
+
+    if check {
+        return Err(io::Error::other(format!(
+            "Table of contents is out of date: {} (run 'mdlibs toc' to update it)",
+            doc_path.display()
+        )));
+    }
+
+    storage::write_document(&lib_root, &doc, &updated)?;
+    println!("Updated table of contents: {}", doc_path.display());
+
+    Ok(())
+}
+
+/// Render the table of contents of a document as a nested list of links.
+///
+/// Headings from level 2 down to `depth` are included; the level 1 title is left out.
+/// Anchors are computed over every heading so repeated headings are numbered as GitHub does.
+fn render_toc(content: &str, depth: usize) -> String {
+    let mut slugger = Slugger::new();
+    let entries: Vec<(usize, String, String)> = headings(content)
+        .into_iter()
+        .map(|heading| {
+            let slug = slugger.slug(&heading.text);
+            (heading.level, plain_text(&heading.text), slug)
+        })
+        .filter(|(level, _, _)| (2..=depth).contains(level))
+        .collect();
+
+    let min_level = entries
+        .iter()
+        .map(|(level, _, _)| *level)
+        .min()
+        .unwrap_or(2);
+    entries
+        .iter()
+        .map(|(level, text, slug)| {
+            format!("{}- [{}](#{})", "  ".repeat(level - min_level), text, slug)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Replace the table of contents block, inserting one after the title if the markers
+/// are missing
+fn set_toc(content: &str, toc: &str) -> String {
+    set_marked_block(content, TOC_START, TOC_END, toc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str =
+        "# Guide\n\n## Install\n\n### From source\n\n#### Details\n\n## Usage\n\n## Usage\n";
+
+    #[test]
+    fn test_render_toc() {
+        assert_eq!(
+            render_toc(DOC, 3),
+            "- [Install](#install)\n  - [From source](#from-source)\n- [Usage](#usage)\n- [Usage](#usage-1)"
+        );
+        assert_eq!(
+            render_toc(DOC, 2),
+            "- [Install](#install)\n- [Usage](#usage)\n- [Usage](#usage-1)"
+        );
+    }
+
+    #[test]
+    fn test_render_toc_uses_rendered_text() {
+        let content = "# Title\n\n## The `run` [function](x.md)\n";
+        assert_eq!(
+            render_toc(content, 3),
+            "- [The run function](#the-run-function)"
+        );
+    }
+
+    #[test]
+    fn test_set_toc_inserts_then_refreshes() {
+        let inserted = set_toc(DOC, &render_toc(DOC, 2));
+        assert!(inserted.starts_with(
+            "# Guide\n\n<!-- toc -->\n- [Install](#install)\n- [Usage](#usage)\n- [Usage](#usage-1)\n<!-- /toc -->\n\n## Install\n"
+        ));
+
+        // Refreshing is idempotent and only touches the block
+        assert_eq!(set_toc(&inserted, &render_toc(&inserted, 2)), inserted);
+        let deeper = set_toc(&inserted, &render_toc(&inserted, 3));
+        assert!(deeper.contains("  - [From source](#from-source)\n"));
+        assert_eq!(deeper.matches(TOC_START).count(), 1);
+    }
+}
@@ -1,12 +1,104 @@
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use crate::commands::list::collect_documents;
 use crate::config::{LibraryConfig, DOCS_DIR, TEMPLATES_DIR};
+use crate::date::Date;
+use crate::frontmatter;
+use crate::git;
+use crate::query::Query;
+use crate::storage::{self, LibraryLock, LoadedDocument};
 use crate::utils::{extract_title_from_content, is_markdown_file};
 
+/// Frontmatter key holding document tags
+const TAGS_KEY: &str = "tags";
+
+/// Frontmatter key holding the document status
+const STATUS_KEY: &str = "status";
+
+/// Frontmatter changes requested on the command line
+#[derive(Debug, Default, Clone)]
+pub struct MetadataEdits {
+    /// Keys to set, with their (YAML) values
+    pub set: Vec<(String, String)>,
+    /// Keys to remove
+    pub unset: Vec<String>,
+    /// Tags to add to the `tags` list
+    pub add_tags: Vec<String>,
+    /// Tags to remove from the `tags` list
+    pub remove_tags: Vec<String>,
+    /// New value for the `status` key
+    pub status: Option<String>,
+}
+
+impl MetadataEdits {
+    /// Whether no edits were requested
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+            && self.unset.is_empty()
+            && self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+            && self.status.is_none()
+    }
+
+    /// Apply the edits to the frontmatter of a document
+    pub fn apply(&self, content: &str) -> String {
+        let mut content = content.to_string();
+        for (key, value) in &self.set {
+            content = frontmatter::set(&content, key, &frontmatter::format_scalar(value));
+        }
+        if let Some(status) = &self.status {
+            content = frontmatter::set(&content, STATUS_KEY, &frontmatter::format_scalar(status));
+        }
+        for key in &self.unset {
+            content = frontmatter::unset(&content, key);
+        }
+        for tag in &self.add_tags {
+            content = frontmatter::add_list_item(&content, TAGS_KEY, tag);
+        }
+        for tag in &self.remove_tags {
+            content = frontmatter::remove_list_item(&content, TAGS_KEY, tag);
+        }
+        content
+    }
+
+    /// Describe the edits for reporting, one line each
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (key, value) in &self.set {
+            lines.push(format!("Set {}: {}", key, value));
+        }
+        if let Some(status) = &self.status {
+            lines.push(format!("Set {}: {}", STATUS_KEY, status));
+        }
+        for key in &self.unset {
+            lines.push(format!("Unset {}", key));
+        }
+        for tag in &self.add_tags {
+            lines.push(format!("Added tag: {}", tag));
+        }
+        for tag in &self.remove_tags {
+            lines.push(format!("Removed tag: {}", tag));
+        }
+        lines
+    }
+}
+
+/// Parse a `key=value` argument for `--set`
+pub fn parse_key_value(arg: &str) -> Result<(String, String), String> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", arg))?;
+    let key = key.trim();
+    if key.is_empty() || key.contains(|c: char| c == ':' || c.is_whitespace()) {
+        return Err(format!("invalid frontmatter key '{}'", key));
+    }
+    Ok((key.to_string(), value.trim().to_string()))
+}
+
 /// Update metadata of a markdown document
-pub fn run(document: &str, title: Option<&str>) -> io::Result<()> {
+pub fn run(document: &str, title: Option<&str>, edits: &MetadataEdits) -> io::Result<()> {
     let current_dir = std::env::current_dir()?;
 
     // Try to find library root
@@ -16,32 +108,163 @@ pub fn run(document: &str, title: Option<&str>) -> io::Result<()> {
     // Find the document
     let doc_path = find_document(&lib_root, document)?;
 
-    if title.is_none() {
+    if title.is_none() && edits.is_empty() {
         // Just display current document info
-        display_document_info(&doc_path)?;
+        display_document_info(&lib_root, &doc_path)?;
         return Ok(());
     }
 
     // Read current content
-    let content = fs::read_to_string(&doc_path)?;
+    let doc = storage::read_document(&doc_path)?;
+
+    // Update title if provided, then frontmatter
+    let mut updated_content = doc.content.clone();
+    if let Some(new_title) = title {
+        updated_content = update_document_title(&updated_content, new_title);
+    }
+    updated_content = edits.apply(&updated_content);
+
+    if updated_content == doc.content {
+        println!("Document unchanged: {}", doc_path.display());
+        return Ok(());
+    }
 
-    // Update title if provided
+    storage::write_document(&lib_root, &doc, &updated_content)?;
+    println!("Updated document: {}", doc_path.display());
     if let Some(new_title) = title {
-        let updated_content = update_document_title(&content, new_title);
-        fs::write(&doc_path, updated_content)?;
-        println!("Updated document: {}", doc_path.display());
         println!("  New title: {}", new_title);
     }
+    for line in edits.describe() {
+        println!("  {}", line);
+    }
 
     Ok(())
 }
 
-/// Find a document by name or path
-fn find_document(lib_root: &Path, document: &str) -> io::Result<std::path::PathBuf> {
+/// Apply metadata edits to every document matching a query.
+///
+/// Without `yes`, only a preview of the documents that would change is printed.
+pub fn run_bulk(query: &str, edits: &MetadataEdits, yes: bool) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    if edits.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "No metadata edits given (use --set, --unset, --add-tag, --remove-tag or --status)",
+        ));
+    }
+    let query = Query::parse(query)?;
+
+    // Hold the lock from planning to writing so the preview stays accurate
+    let _lock = if yes {
+        Some(LibraryLock::acquire(&lib_root)?)
+    } else {
+        None
+    };
+    let planned = plan_bulk_update(&lib_root, &query, edits)?;
+
+    if planned.is_empty() {
+        println!("No documents would change.");
+        return Ok(());
+    }
+
+    println!(
+        "{} {} document(s):",
+        if yes { "Updating" } else { "Would update" },
+        planned.len()
+    );
+    for (doc, _) in &planned {
+        let relative = doc.path.strip_prefix(&lib_root).unwrap_or(&doc.path);
+        println!("  {}", relative.display());
+    }
+    for line in edits.describe() {
+        println!("  {}", line);
+    }
+
+    if !yes {
+        println!("\nRe-run with --yes to apply these changes.");
+        return Ok(());
+    }
+
+    for (doc, content) in &planned {
+        storage::write_document(&lib_root, doc, content)?;
+    }
+    println!("\nUpdated {} document(s).", planned.len());
+
+    Ok(())
+}
+
+/// Read every document matching a query and compute its edited content, keeping only
+/// the documents the edits would change
+fn plan_bulk_update(
+    lib_root: &Path,
+    query: &Query,
+    edits: &MetadataEdits,
+) -> io::Result<Vec<(LoadedDocument, String)>> {
+    let mut planned = Vec::new();
+    for entry in collect_documents(lib_root)? {
+        let doc = storage::read_document(&lib_root.join(&entry.path))?;
+        if !query.matches(&entry, &doc.content) {
+            continue;
+        }
+        let updated = edits.apply(&doc.content);
+        if updated != doc.content {
+            planned.push((doc, updated));
+        }
+    }
+    planned.sort_by(|a, b| a.0.path.cmp(&b.0.path));
+    Ok(planned)
+}
+
+/// Find a document by name, path or title, failing if more than one document matches
+pub fn find_document(lib_root: &Path, document: &str) -> io::Result<PathBuf> {
+    let mut candidates = find_document_candidates(lib_root, document)?;
+    match candidates.len() {
+        0 => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Document not found: {}", document),
+        )),
+        1 => Ok(candidates.remove(0)),
+        _ => {
+            let lib_canonical = lib_root.canonicalize()?;
+            let list: Vec<String> = candidates
+                .iter()
+                .map(|path| {
+                    let relative = path.strip_prefix(&lib_canonical).unwrap_or(path);
+                    format!("  {}", relative.display())
+                })
+                .collect();
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Ambiguous document '{}' matches {} documents:\n{}",
+                    document,
+                    candidates.len(),
+                    list.join("\n")
+                ),
+            ))
+        }
+    }
+}
+
+/// Find every document matching a name, path or title.
+///
+/// If `document` names an existing file relative to the library root or the current
+/// directory (or by absolute path), that file is the only candidate. Otherwise a document
+/// matches if `document` (with or without `.md`) is its path relative to the library
+/// root, `docs/` or `templates/`, if its path ends with `document` (so bare file names
+/// find nested files), or if its title equals `document` ignoring case.
+/// Returns canonical paths, sorted and without duplicates.
+pub fn find_document_candidates(lib_root: &Path, document: &str) -> io::Result<Vec<PathBuf>> {
+    let lib_canonical = lib_root.canonicalize()?;
+
     // Helper to validate path is within library and is a markdown file
-    let validate_path = |path: &Path| -> io::Result<std::path::PathBuf> {
+    let validate_path = |path: &Path| -> io::Result<PathBuf> {
         let canonical = path.canonicalize()?;
-        let lib_canonical = lib_root.canonicalize()?;
 
         // Check path is within library root (prevent path traversal)
         if !canonical.starts_with(&lib_canonical) {
@@ -62,50 +285,65 @@ fn find_document(lib_root: &Path, document: &str) -> io::Result<std::path::PathB
         Ok(canonical)
     };
 
-    // Try direct path first
-    let direct_path = lib_root.join(document);
-    if direct_path.exists() {
-        return validate_path(&direct_path);
-    }
-
-    // Try in docs directory
-    let docs_path = lib_root.join(DOCS_DIR).join(document);
-    if docs_path.exists() {
-        return validate_path(&docs_path);
-    }
-
-    // Try in templates directory
-    let templates_path = lib_root.join(TEMPLATES_DIR).join(document);
-    if templates_path.exists() {
-        return validate_path(&templates_path);
+    // A path naming an existing file is never ambiguous
+    let current_dir = std::env::current_dir()?;
+    for base in [lib_root, current_dir.as_path()] {
+        let path = base.join(document);
+        if path.is_file() {
+            return Ok(vec![validate_path(&path)?]);
+        }
     }
 
-    // Try adding .md extension
     let with_ext = format!("{}.md", document);
-
-    let direct_with_ext = lib_root.join(&with_ext);
-    if direct_with_ext.exists() {
-        return validate_path(&direct_with_ext);
+    let mut candidates = Vec::new();
+    let mut first_error = None;
+
+    // Explicit paths relative to the library root, docs and templates directories
+    for base in [
+        lib_root.to_path_buf(),
+        lib_root.join(DOCS_DIR),
+        lib_root.join(TEMPLATES_DIR),
+    ] {
+        for name in [document, with_ext.as_str()] {
+            let path = base.join(name);
+            if !path.exists() {
+                continue;
+            }
+            match validate_path(&path) {
+                Ok(canonical) => candidates.push(canonical),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
     }
 
-    let docs_with_ext = lib_root.join(DOCS_DIR).join(&with_ext);
-    if docs_with_ext.exists() {
-        return validate_path(&docs_with_ext);
+    // Nested files by name or partial path, and documents by title
+    let title = document.to_lowercase();
+    for doc in collect_documents(lib_root)? {
+        let relative = Path::new(&doc.path);
+        let matches = relative.ends_with(document)
+            || relative.ends_with(&with_ext)
+            || doc.title.to_lowercase() == title;
+        if matches {
+            if let Ok(canonical) = validate_path(&lib_root.join(relative)) {
+                candidates.push(canonical);
+            }
+        }
     }
 
-    let templates_with_ext = lib_root.join(TEMPLATES_DIR).join(&with_ext);
-    if templates_with_ext.exists() {
-        return validate_path(&templates_with_ext);
-    }
+    candidates.sort();
+    candidates.dedup();
 
-    Err(io::Error::new(
-        io::ErrorKind::NotFound,
-        format!("Document not found: {}", document),
-    ))
+    match first_error {
+        // Report why an explicitly named path was rejected if nothing else matched
+        Some(e) if candidates.is_empty() => Err(e),
+        _ => Ok(candidates),
+    }
 }
 
 /// Display information about a document
-fn display_document_info(path: &Path) -> io::Result<()> {
+fn display_document_info(lib_root: &Path, path: &Path) -> io::Result<()> {
     let content = fs::read_to_string(path)?;
     let title = extract_title_from_content(&content).unwrap_or_else(|| "Untitled".to_string());
     let word_count = count_words(&content);
@@ -116,9 +354,60 @@ fn display_document_info(path: &Path) -> io::Result<()> {
     println!("  Lines: {}", line_count);
     println!("  Words: {}", word_count);
 
+    let metadata = document_metadata(lib_root, path, &content);
+    for (label, value) in [
+        ("Created", metadata.created),
+        ("Updated", metadata.updated),
+        ("Authors", metadata.authors),
+    ] {
+        if let Some(value) = value {
+            println!("  {}: {}", label, value);
+        }
+    }
+
     Ok(())
 }
 
+/// Authorship metadata of a document
+#[derive(Debug, Default, PartialEq)]
+pub struct DocumentMetadata {
+    pub created: Option<String>,
+    pub updated: Option<String>,
+    pub authors: Option<String>,
+}
+
+/// Read `created`, `updated` and `authors` from frontmatter, deriving any missing
+/// field from the document's git history when the library is a git repository
+pub fn document_metadata(lib_root: &Path, path: &Path, content: &str) -> DocumentMetadata {
+    let mut metadata = DocumentMetadata {
+        created: frontmatter::get(content, "created"),
+        updated: frontmatter::get(content, "updated"),
+        authors: frontmatter::get_list(content, "authors")
+            .filter(|authors| !authors.is_empty())
+            .map(|authors| authors.join(", ")),
+    };
+
+    let complete =
+        metadata.created.is_some() && metadata.updated.is_some() && metadata.authors.is_some();
+    if complete || !git::is_repository(lib_root) {
+        return metadata;
+    }
+
+    let history = git::file_history(lib_root, path).unwrap_or_default();
+    let commit_date =
+        |commit: &git::Commit| Date::from_unix_timestamp(commit.timestamp).to_string();
+    if metadata.created.is_none() {
+        metadata.created = history.last().map(commit_date);
+    }
+    if metadata.updated.is_none() {
+        metadata.updated = history.first().map(commit_date);
+    }
+    if metadata.authors.is_none() && !history.is_empty() {
+        metadata.authors = Some(git::authors(&history).join(", "));
+    }
+    metadata
+}
+
 /// Update the title (first H1 heading) in a markdown document
 fn update_document_title(content: &str, new_title: &str) -> String {
     // Preserve whether the original content ended with a newline
@@ -126,8 +415,10 @@ fn update_document_title(content: &str, new_title: &str) -> String {
 
     let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
     let mut found_title = false;
+    // Frontmatter comments also start with "# ", so skip the frontmatter block
+    let body_start = frontmatter::body_start(&lines);
 
-    for line in &mut lines {
+    for line in &mut lines[body_start..] {
         if line.trim().starts_with("# ") {
             *line = format!("# {}", new_title);
             found_title = true;
@@ -136,9 +427,9 @@ fn update_document_title(content: &str, new_title: &str) -> String {
     }
 
     if !found_title {
-        // Prepend title if none exists, followed by empty line
-        lines.insert(0, String::new());
-        lines.insert(0, format!("# {}", new_title));
+        // Prepend title (after any frontmatter) if none exists, followed by empty line
+        lines.insert(body_start, String::new());
+        lines.insert(body_start, format!("# {}", new_title));
     }
 
     let mut result = lines.join("\n");
@@ -157,6 +448,141 @@ fn count_words(content: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::env;
+
+    /// Create a library with documents at the given relative paths
+    fn setup_library(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let temp_dir = env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&temp_dir);
+        for (path, content) in files {
+            let path = temp_dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        temp_dir
+    }
+
+    #[test]
+    fn test_plan_bulk_update() {
+        let lib = setup_library(
+            "mdlibs_test_update_bulk",
+            &[
+                ("docs/a.md", "---\nstatus: draft\n---\n# A\n"),
+                ("docs/b.md", "---\nstatus: draft\ntags: [old]\n---\n# B\n"),
+                ("docs/c.md", "---\nstatus: published\n---\n# C\n"),
+                ("docs/d.md", "---\nstatus: archived\n---\n# D\n"),
+            ],
+        );
+        let edits = MetadataEdits {
+            status: Some(String::from("archived")),
+            ..Default::default()
+        };
+
+        let query = Query::parse("status:draft").unwrap();
+        let planned = plan_bulk_update(&lib, &query, &edits).unwrap();
+        let paths: Vec<_> = planned.iter().map(|(doc, _)| doc.path.clone()).collect();
+        assert_eq!(paths, vec![lib.join("docs/a.md"), lib.join("docs/b.md")]);
+        assert_eq!(
+            planned[1].1,
+            "---\nstatus: archived\ntags: [old]\n---\n# B\n"
+        );
+
+        // Documents the edits would not change are left out
+        let query = Query::parse("path:docs/d").unwrap();
+        assert!(plan_bulk_update(&lib, &query, &edits).unwrap().is_empty());
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
+
+    #[test]
+    fn test_find_document_by_name_and_path() {
+        let lib = setup_library(
+            "mdlibs_test_find_unique",
+            &[
+                ("docs/guide.md", "# Guide"),
+                ("docs/setup/linux.md", "# Linux"),
+            ],
+        );
+
+        assert!(find_document(&lib, "guide")
+            .unwrap()
+            .ends_with("docs/guide.md"));
+        assert!(find_document(&lib, "docs/guide.md")
+            .unwrap()
+            .ends_with("docs/guide.md"));
+        // Nested files are found by bare name and by partial path
+        assert!(find_document(&lib, "linux.md")
+            .unwrap()
+            .ends_with("setup/linux.md"));
+        assert!(find_document(&lib, "setup/linux")
+            .unwrap()
+            .ends_with("setup/linux.md"));
+        // And by title
+        assert!(find_document(&lib, "LINUX")
+            .unwrap()
+            .ends_with("setup/linux.md"));
+
+        let err = find_document(&lib, "missing").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
+
+    #[test]
+    fn test_find_document_ambiguous() {
+        let lib = setup_library(
+            "mdlibs_test_find_ambiguous",
+            &[
+                ("docs/foo.md", "# Foo"),
+                ("templates/foo.md", "# Foo template"),
+            ],
+        );
+
+        let err = find_document(&lib, "foo").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let message = err.to_string();
+        assert!(message.contains("docs/foo.md"));
+        assert!(message.contains("templates/foo.md"));
+
+        // An explicit path is still unambiguous
+        assert!(find_document(&lib, "templates/foo.md").is_ok());
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
+
+    #[test]
+    fn test_find_document_exact_path_in_init_layout() {
+        // `init` creates both a root README.md and docs/README.md
+        let lib = setup_library(
+            "mdlibs_test_find_exact",
+            &[("README.md", "# Library"), ("docs/README.md", "# Docs")],
+        );
+
+        for query in ["README.md", "./README.md"] {
+            let path = find_document(&lib, query).unwrap();
+            assert_eq!(path, lib.join("README.md").canonicalize().unwrap());
+        }
+        assert!(find_document(&lib, "docs/README.md")
+            .unwrap()
+            .ends_with("docs/README.md"));
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
+
+    #[test]
+    fn test_find_document_rejects_non_markdown() {
+        let lib = setup_library("mdlibs_test_find_non_md", &[("docs/notes.txt", "text")]);
+
+        let err = find_document(&lib, "docs/notes.txt").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
 
     #[test]
     fn test_update_document_title_existing() {
@@ -175,6 +601,16 @@ mod tests {
         assert!(result.contains("# New Title\n\n"));
     }
 
+    #[test]
+    fn test_update_document_title_with_frontmatter() {
+        let content = "---\n# a YAML comment\nstatus: draft\n---\nBody\n";
+        let result = update_document_title(content, "New Title");
+        assert_eq!(
+            result,
+            "---\n# a YAML comment\nstatus: draft\n---\n# New Title\n\nBody\n"
+        );
+    }
+
     #[test]
     fn test_update_document_title_preserves_no_trailing_newline() {
         let content = "# Old Title\n\nContent without trailing newline";
@@ -199,6 +635,69 @@ mod tests {
         assert!(!result.ends_with('\n'));
     }
 
+    #[test]
+    fn test_document_metadata_from_frontmatter() {
+        let lib = setup_library("mdlibs_test_update_metadata", &[]);
+        fs::create_dir_all(&lib).unwrap();
+        let content = "---\ncreated: 2026-01-01\nupdated: 2026-02-01\nauthors: [ann]\n---\n# Doc\n";
+
+        let metadata = document_metadata(&lib, &lib.join("doc.md"), content);
+        assert_eq!(metadata.created, Some("2026-01-01".to_string()));
+        assert_eq!(metadata.updated, Some("2026-02-01".to_string()));
+        assert_eq!(metadata.authors, Some("ann".to_string()));
+
+        // Outside a git repository nothing is derived
+        let metadata = document_metadata(&lib, &lib.join("doc.md"), "# Doc\n");
+        assert_eq!(metadata, DocumentMetadata::default());
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
+
+    #[test]
+    fn test_metadata_edits_apply() {
+        let content = "---\ntitle: Doc # keep\ntags: [a]\nowner: me\n---\n# Doc\n";
+        let edits = MetadataEdits {
+            set: vec![("reviewed".to_string(), "yes".to_string())],
+            unset: vec!["owner".to_string()],
+            add_tags: vec!["b".to_string()],
+            remove_tags: vec!["a".to_string()],
+            status: Some("archived".to_string()),
+        };
+        assert_eq!(
+            edits.apply(content),
+            "---\ntitle: Doc # keep\ntags: [b]\nreviewed: yes\nstatus: archived\n---\n# Doc\n"
+        );
+    }
+
+    #[test]
+    fn test_metadata_edits_create_frontmatter() {
+        let edits = MetadataEdits {
+            status: Some("draft".to_string()),
+            ..MetadataEdits::default()
+        };
+        assert_eq!(
+            edits.apply("# Doc\n\nBody\n"),
+            "---\nstatus: draft\n---\n# Doc\n\nBody\n"
+        );
+        assert!(!edits.is_empty());
+        assert!(MetadataEdits::default().is_empty());
+    }
+
+    #[test]
+    fn test_parse_key_value() {
+        assert_eq!(
+            parse_key_value("status=in review"),
+            Ok(("status".to_string(), "in review".to_string()))
+        );
+        assert_eq!(
+            parse_key_value("url=https://x.y/?a=b"),
+            Ok(("url".to_string(), "https://x.y/?a=b".to_string()))
+        );
+        assert!(parse_key_value("no-equals").is_err());
+        assert!(parse_key_value("bad key=x").is_err());
+    }
+
     #[test]
     fn test_count_words() {
         let content = "One two three four five";
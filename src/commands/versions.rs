@@ -0,0 +1,125 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::commands::update::find_document;
+use crate::config::LibraryConfig;
+use crate::date::format_timestamp;
+use crate::diff::unified_diff;
+use crate::snapshot;
+use crate::storage;
+
+/// Lines of context shown around each change in a diff
+const DIFF_CONTEXT: usize = 3;
+
+/// List the stored snapshots of a document
+pub fn run(document: &str) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    let doc_path = find_document(&lib_root, document)?;
+    let versions = snapshot::versions(&lib_root, &doc_path)?;
+
+    if versions.is_empty() {
+        println!("No versions recorded for: {}", doc_path.display());
+        println!("Hint: mdlibs records a snapshot before each change it makes to a document.");
+        return Ok(());
+    }
+
+    println!(
+        "Found {} version(s) of {}:\n",
+        versions.len(),
+        doc_path.display()
+    );
+    println!("{:<10} {:<18} {:>8}", "REV", "DATE (UTC)", "BYTES");
+    println!("{}", "-".repeat(40));
+
+    // Newest first, like `history`
+    for version in versions.iter().rev() {
+        let size = snapshot::read(&lib_root, version)
+            .map(|content| content.len().to_string())
+            .unwrap_or_else(|_| String::from("missing"));
+        println!(
+            "{:<10} {:<18} {:>8}",
+            version.short_hash(),
+            format_timestamp(version.timestamp),
+            size
+        );
+    }
+
+    Ok(())
+}
+
+/// Show the changes between a snapshot (the latest by default) and the current document
+pub fn run_diff(document: &str, rev: Option<&str>) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    let doc_path = find_document(&lib_root, document)?;
+    let version = snapshot::resolve(&lib_root, &doc_path, rev)?;
+    let old = snapshot::read(&lib_root, &version)?;
+    let current = fs::read_to_string(&doc_path)?;
+
+    match diff_against_current(&doc_path, &version, &old, &current) {
+        Some(diff) => print!("{}", diff),
+        None => println!(
+            "No differences between {} and the current document.",
+            version.short_hash()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Restore a document to a stored snapshot
+pub fn run_restore(document: &str, rev: &str) -> io::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    // Try to find library root
+    let lib_root =
+        LibraryConfig::find_library_root(&current_dir).unwrap_or_else(|| current_dir.clone());
+
+    let doc_path = find_document(&lib_root, document)?;
+    let version = snapshot::resolve(&lib_root, &doc_path, Some(rev))?;
+    let content = snapshot::read(&lib_root, &version)?;
+
+    let doc = storage::read_document(&doc_path)?;
+    if doc.content == content {
+        println!(
+            "Document already matches {}: {}",
+            version.short_hash(),
+            doc_path.display()
+        );
+        return Ok(());
+    }
+
+    // The current content is snapshotted, so the restore itself can be undone
+    storage::write_document(&lib_root, &doc, &content)?;
+
+    println!("Restored document: {}", doc_path.display());
+    println!("  Revision: {}", version.short_hash());
+    Ok(())
+}
+
+/// Render the diff from a snapshot to the current content
+fn diff_against_current(
+    path: &Path,
+    version: &snapshot::Version,
+    old: &str,
+    current: &str,
+) -> Option<String> {
+    let name = path.display();
+    unified_diff(
+        old,
+        current,
+        &format!("{}@{}", name, version.short_hash()),
+        &format!("{}", name),
+        DIFF_CONTEXT,
+    )
+}
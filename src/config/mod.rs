@@ -11,6 +11,33 @@ pub const TEMPLATES_DIR: &str = "templates";
 /// Default directory for documents
 pub const DOCS_DIR: &str = "docs";
 
+/// Directory holding mdlibs' own state (snapshots, locks), relative to the library root
+pub const STATE_DIR: &str = ".mdlibs";
+
+/// Default directory for journal entries
+pub const JOURNAL_DIR: &str = "docs/journal";
+
+/// Default template for journal entries (relative to the templates directory)
+pub const JOURNAL_TEMPLATE: &str = "journal.md";
+
+/// Default location of the generated index page
+pub const INDEX_FILE: &str = "docs/index.md";
+
+/// Default grouping of the generated index page
+pub const INDEX_GROUP_BY: &str = "directory";
+
+/// Default maximum line length checked by `mdlibs lint`
+pub const LINT_LINE_LENGTH: usize = 100;
+
+/// Default line width used by `mdlibs fmt`
+pub const FMT_WIDTH: usize = 80;
+
+/// Default number of threads used to scan and read documents (0 picks one per CPU)
+pub const WALK_THREADS: usize = 0;
+
+/// Default documents that are not expected to have inbound links
+pub const LINKS_ENTRY_POINTS: [&str; 3] = ["README.md", "docs/README.md", "docs/index.md"];
+
 /// Library configuration
 #[derive(Debug, Clone)]
 pub struct LibraryConfig {
@@ -18,6 +45,151 @@ pub struct LibraryConfig {
     #[allow(dead_code)]
     pub path: PathBuf,
     pub version: String,
+    pub journal: JournalConfig,
+    pub index: IndexConfig,
+    pub lint: LintConfig,
+    pub fmt: FmtConfig,
+    pub walk: WalkConfig,
+    pub links: LinksConfig,
+}
+
+/// Journal configuration (`[journal]` section)
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalConfig {
+    /// Directory holding dated entries, relative to the library root
+    pub dir: String,
+    /// Template file for new entries, relative to the templates directory
+    pub template: String,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            dir: String::from(JOURNAL_DIR),
+            template: String::from(JOURNAL_TEMPLATE),
+        }
+    }
+}
+
+/// Index page configuration (`[index]` section)
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexConfig {
+    /// Location of the index document, relative to the library root
+    pub path: String,
+    /// How documents are grouped: `directory`, `tag` or `type`
+    pub group_by: String,
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            path: String::from(INDEX_FILE),
+            group_by: String::from(INDEX_GROUP_BY),
+        }
+    }
+}
+
+/// Lint rule configuration (`[lint]` section)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintConfig {
+    /// Report documents without a level 1 heading
+    pub missing_h1: bool,
+    /// Report documents with more than one level 1 heading
+    pub multiple_h1: bool,
+    /// Report headings that skip a level (e.g. `##` followed by `####`)
+    pub heading_increment: bool,
+    /// Report headings without exactly one space after the `#`s or without blank lines
+    /// around them
+    pub heading_spacing: bool,
+    /// Report trailing whitespace (two spaces, a hard line break, are allowed)
+    pub trailing_whitespace: bool,
+    /// Report documents that do not end with a newline
+    pub final_newline: bool,
+    /// Report URLs that are not written as links
+    pub bare_url: bool,
+    /// Unordered list marker style: `consistent`, `-`, `*`, `+` or `off`
+    pub list_marker: String,
+    /// Maximum line length outside code blocks (0 disables the rule)
+    pub line_length: usize,
+    /// Frontmatter keys every document must have
+    pub required_frontmatter: Vec<String>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            missing_h1: true,
+            multiple_h1: true,
+            heading_increment: true,
+            heading_spacing: true,
+            trailing_whitespace: true,
+            final_newline: true,
+            bare_url: true,
+            list_marker: String::from("consistent"),
+            line_length: LINT_LINE_LENGTH,
+            required_frontmatter: Vec::new(),
+        }
+    }
+}
+
+/// Formatter configuration (`[fmt]` section)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FmtConfig {
+    /// Width paragraphs are wrapped at (0 keeps existing line breaks)
+    pub width: usize,
+}
+
+impl Default for FmtConfig {
+    fn default() -> Self {
+        Self { width: FMT_WIDTH }
+    }
+}
+
+/// Directory traversal configuration (`[walk]` section)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalkConfig {
+    /// Threads used to scan directories and read documents (0 uses one per CPU)
+    pub threads: usize,
+    /// Gitignore-style patterns of paths to leave out, relative to the library root
+    pub exclude: Vec<String>,
+    /// Also leave out paths matched by `.gitignore` files
+    pub gitignore: bool,
+    /// Include hidden files and directories (names starting with `.`)
+    pub hidden: bool,
+    /// Follow symbolic links to files and directories; when false they are skipped
+    pub follow_symlinks: bool,
+    /// Directory levels scanned below `docs/` and `templates/` (1 reads only their own
+    /// files; 0 scans the whole tree)
+    pub max_depth: usize,
+}
+
+impl Default for WalkConfig {
+    fn default() -> Self {
+        Self {
+            threads: WALK_THREADS,
+            exclude: Vec::new(),
+            gitignore: true,
+            hidden: false,
+            follow_symlinks: true,
+            max_depth: 0,
+        }
+    }
+}
+
+/// Link analysis configuration (`[links]` section)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinksConfig {
+    /// Documents readers start from, which are not reported as orphans, relative to the
+    /// library root
+    pub entry_points: Vec<String>,
+}
+
+impl Default for LinksConfig {
+    fn default() -> Self {
+        Self {
+            entry_points: LINKS_ENTRY_POINTS.iter().map(|s| s.to_string()).collect(),
+        }
+    }
 }
 
 impl Default for LibraryConfig {
@@ -26,6 +198,12 @@ impl Default for LibraryConfig {
             name: String::from("mdlibs"),
             path: PathBuf::from("."),
             version: String::from("0.1.0"),
+            journal: JournalConfig::default(),
+            index: IndexConfig::default(),
+            lint: LintConfig::default(),
+            fmt: FmtConfig::default(),
+            walk: WalkConfig::default(),
+            links: LinksConfig::default(),
         }
     }
 }
@@ -37,26 +215,101 @@ impl LibraryConfig {
             name: name.to_string(),
             path,
             version: String::from("0.1.0"),
+            journal: JournalConfig::default(),
+            index: IndexConfig::default(),
+            lint: LintConfig::default(),
+            fmt: FmtConfig::default(),
+            walk: WalkConfig::default(),
+            links: LinksConfig::default(),
         }
     }
 
     /// Generate the config file content as TOML format
     pub fn to_toml(&self) -> String {
         // Escape special characters in TOML string values
-        let escaped_name = self.name.replace('\\', "\\\\").replace('"', "\\\"");
-        let escaped_version = self.version.replace('\\', "\\\\").replace('"', "\\\"");
         format!(
             r#"# mdlibs configuration file
 [library]
 name = "{}"
 version = "{}"
+
+[journal]
+dir = "{}"
+template = "{}"
+
+[index]
+path = "{}"
+group_by = "{}"
+
+[lint]
+missing_h1 = {}
+multiple_h1 = {}
+heading_increment = {}
+heading_spacing = {}
+trailing_whitespace = {}
+final_newline = {}
+bare_url = {}
+list_marker = "{}"
+line_length = {}
+required_frontmatter = [{}]
+
+[fmt]
+width = {}
+
+[walk]
+threads = {}
+exclude = [{}]
+gitignore = {}
+hidden = {}
+follow_symlinks = {}
+max_depth = {}
+
+[links]
+entry_points = [{}]
 "#,
-            escaped_name, escaped_version
+            escape_toml_string(&self.name),
+            escape_toml_string(&self.version),
+            escape_toml_string(&self.journal.dir),
+            escape_toml_string(&self.journal.template),
+            escape_toml_string(&self.index.path),
+            escape_toml_string(&self.index.group_by),
+            self.lint.missing_h1,
+            self.lint.multiple_h1,
+            self.lint.heading_increment,
+            self.lint.heading_spacing,
+            self.lint.trailing_whitespace,
+            self.lint.final_newline,
+            self.lint.bare_url,
+            escape_toml_string(&self.lint.list_marker),
+            self.lint.line_length,
+            self.lint
+                .required_frontmatter
+                .iter()
+                .map(|key| format!("\"{}\"", escape_toml_string(key)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.fmt.width,
+            self.walk.threads,
+            self.walk
+                .exclude
+                .iter()
+                .map(|glob| format!("\"{}\"", escape_toml_string(glob)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.walk.gitignore,
+            self.walk.hidden,
+            self.walk.follow_symlinks,
+            self.walk.max_depth,
+            self.links
+                .entry_points
+                .iter()
+                .map(|path| format!("\"{}\"", escape_toml_string(path)))
+                .collect::<Vec<_>>()
+                .join(", ")
         )
     }
 
     /// Load configuration from a path
-    #[allow(dead_code)]
     pub fn load(path: &Path) -> io::Result<Self> {
         let config_path = path.join(CONFIG_FILE_NAME);
         if !config_path.exists() {
@@ -70,28 +323,88 @@ version = "{}"
         Self::parse_toml(&content, path)
     }
 
+    /// Load configuration from a path, falling back to defaults when there is no config file
+    pub fn load_or_default(path: &Path) -> io::Result<Self> {
+        if path.join(CONFIG_FILE_NAME).exists() {
+            Self::load(path)
+        } else {
+            Ok(Self {
+                path: path.to_path_buf(),
+                ..Self::default()
+            })
+        }
+    }
+
     /// Parse TOML content (simple parser for our format)
     fn parse_toml(content: &str, path: &Path) -> io::Result<Self> {
         let mut name = String::from("mdlibs");
         let mut version = String::from("0.1.0");
+        let mut journal = JournalConfig::default();
+        let mut index = IndexConfig::default();
+        let mut lint = LintConfig::default();
+        let mut fmt = FmtConfig::default();
+        let mut walk = WalkConfig::default();
+        let mut links = LinksConfig::default();
+        let mut section = String::new();
 
-        for line in content.lines() {
-            let line = line.trim();
+        let mut lines = content.lines();
+        while let Some(line) = lines.next() {
+            let mut line = strip_comment(line).trim().to_string();
             // Skip comments and empty lines
-            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // Track the current [section]
+            if let Some(header) = line.strip_prefix('[') {
+                section = header.trim_end_matches(']').trim().to_string();
                 continue;
             }
+            // An array may continue over the following lines until its closing bracket
+            while array_is_open(&line) {
+                let Some(next) = lines.next() else {
+                    break;
+                };
+                line.push(' ');
+                line.push_str(strip_comment(next).trim());
+            }
             // Check for exact key match (key = value format)
             if let Some((key, _)) = line.split_once('=') {
                 let key = key.trim();
-                if key == "name" {
-                    if let Some(value) = Self::extract_toml_value(line) {
-                        name = value;
+                let Some(value) = Self::extract_toml_value(&line) else {
+                    continue;
+                };
+                match (section.as_str(), key) {
+                    ("" | "library", "name") => name = value,
+                    ("" | "library", "version") => version = value,
+                    ("journal", "dir") => journal.dir = value,
+                    ("journal", "template") => journal.template = value,
+                    ("index", "path") => index.path = value,
+                    ("index", "group_by") => index.group_by = value,
+                    ("lint", "missing_h1") => lint.missing_h1 = parse_bool(key, &value)?,
+                    ("lint", "multiple_h1") => lint.multiple_h1 = parse_bool(key, &value)?,
+                    ("lint", "heading_increment") => {
+                        lint.heading_increment = parse_bool(key, &value)?
+                    }
+                    ("lint", "trailing_whitespace") => {
+                        lint.trailing_whitespace = parse_bool(key, &value)?
                     }
-                } else if key == "version" {
-                    if let Some(value) = Self::extract_toml_value(line) {
-                        version = value;
+                    ("lint", "heading_spacing") => lint.heading_spacing = parse_bool(key, &value)?,
+                    ("lint", "final_newline") => lint.final_newline = parse_bool(key, &value)?,
+                    ("lint", "bare_url") => lint.bare_url = parse_bool(key, &value)?,
+                    ("lint", "list_marker") => lint.list_marker = value,
+                    ("lint", "line_length") => lint.line_length = parse_number(key, &value)?,
+                    ("fmt", "width") => fmt.width = parse_number(key, &value)?,
+                    ("walk", "threads") => walk.threads = parse_number(key, &value)?,
+                    ("walk", "exclude") => walk.exclude = parse_string_array(&value),
+                    ("walk", "gitignore") => walk.gitignore = parse_bool(key, &value)?,
+                    ("walk", "hidden") => walk.hidden = parse_bool(key, &value)?,
+                    ("walk", "follow_symlinks") => walk.follow_symlinks = parse_bool(key, &value)?,
+                    ("walk", "max_depth") => walk.max_depth = parse_number(key, &value)?,
+                    ("lint", "required_frontmatter") => {
+                        lint.required_frontmatter = parse_string_array(&value)
                     }
+                    ("links", "entry_points") => links.entry_points = parse_string_array(&value),
+                    _ => {}
                 }
             }
         }
@@ -100,17 +413,23 @@ version = "{}"
             name,
             path: path.to_path_buf(),
             version,
+            journal,
+            index,
+            lint,
+            fmt,
+            walk,
+            links,
         })
     }
 
-    /// Extract value from a TOML key-value line
+    /// Extract value from a TOML key-value line, without a trailing comment
     fn extract_toml_value(line: &str) -> Option<String> {
-        let parts: Vec<&str> = line.splitn(2, '=').collect();
-        if parts.len() == 2 {
-            let value = parts[1].trim().trim_matches('"');
+        let (_, value) = line.split_once('=')?;
+        let value = strip_comment(value).trim();
+        if value.starts_with('[') {
             Some(value.to_string())
         } else {
-            None
+            Some(unquote(value))
         }
     }
 
@@ -130,6 +449,130 @@ version = "{}"
     }
 }
 
+/// Escape special characters in a TOML string value
+fn escape_toml_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Parse a TOML boolean value
+fn parse_bool(key: &str, value: &str) -> io::Result<bool> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(invalid_value(key, value)),
+    }
+}
+
+/// Parse a TOML integer value
+fn parse_number(key: &str, value: &str) -> io::Result<usize> {
+    value.parse().map_err(|_| invalid_value(key, value))
+}
+
+/// Remove a `#` comment that is not inside a quoted string
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &text[..i],
+            None => {}
+        }
+    }
+    text
+}
+
+/// Whether a key-value line holds an array whose closing bracket is on a later line
+fn array_is_open(line: &str) -> bool {
+    let Some((_, value)) = line.split_once('=') else {
+        return false;
+    };
+    let value = strip_comment(value).trim();
+    if !value.starts_with('[') {
+        return false;
+    }
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut escaped = false;
+    for c in value.chars() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '[' => depth += 1,
+            None if c == ']' => depth -= 1,
+            None => {}
+        }
+    }
+    depth > 0
+}
+
+/// Remove the quotes around a TOML string, resolving `\"` and `\\` escapes in basic strings
+fn unquote(value: &str) -> String {
+    if let Some(literal) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return literal.to_string();
+    }
+    let Some(basic) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+    let mut result = String::with_capacity(basic.len());
+    let mut chars = basic.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            result.extend(chars.next());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Parse a TOML array of strings (`["a", "b"]`), which may have spanned several lines
+fn parse_string_array(value: &str) -> Vec<String> {
+    let inner = value.trim().trim_start_matches('[').trim_end_matches(']');
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut quote = None;
+    let mut escaped = false;
+    for c in inner.chars() {
+        match quote {
+            Some(_) if escaped => {
+                item.push(c);
+                escaped = false;
+            }
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => item.push(c),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ',' => {
+                items.push(std::mem::take(&mut item));
+            }
+            None if c.is_whitespace() => {}
+            None => item.push(c),
+        }
+    }
+    items.push(item);
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Error for a config value of the wrong type
+fn invalid_value(key: &str, value: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "Invalid value for {} in {}: {}",
+            key, CONFIG_FILE_NAME, value
+        ),
+    )
+}
+
 /// Plugin trait for future extensibility
 #[allow(dead_code)]
 pub trait Plugin {
@@ -179,6 +622,36 @@ mod tests {
             LibraryConfig::extract_toml_value("version = \"1.0.0\""),
             Some("1.0.0".to_string())
         );
+        assert_eq!(
+            LibraryConfig::extract_toml_value("threads = 2 # keep low"),
+            Some("2".to_string())
+        );
+        assert_eq!(
+            LibraryConfig::extract_toml_value("dir = \"notes #1\" # comment"),
+            Some("notes #1".to_string())
+        );
+        assert_eq!(
+            LibraryConfig::extract_toml_value("name = \"say \\\"hi\\\"\""),
+            Some("say \"hi\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_string_array() {
+        assert_eq!(
+            parse_string_array("[\"a, b\", 'c', \"d\\\"e\"]"),
+            vec!["a, b", "c", "d\"e"]
+        );
+        assert_eq!(parse_string_array("[]"), Vec::<String>::new());
+        assert_eq!(parse_string_array("[\"a\", \"b\",]"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_parse_toml_multi_line_array() {
+        let content = "[walk] # scanning\nexclude = [\n  \"build/\", # generated\n  \"a]b\",\n]\nthreads = 2 # keep low\n";
+        let config = LibraryConfig::parse_toml(content, Path::new(".")).unwrap();
+        assert_eq!(config.walk.exclude, vec!["build/", "a]b"]);
+        assert_eq!(config.walk.threads, 2);
     }
 
     #[test]
@@ -218,10 +691,109 @@ version = "1.0.0"
         assert_eq!(config.name, "correct");
     }
 
+    #[test]
+    fn test_parse_toml_journal_section() {
+        let content = r#"
+[library]
+name = "notes"
+
+[journal]
+dir = "journal"
+template = "daily.md"
+"#;
+        let config = LibraryConfig::parse_toml(content, Path::new(".")).unwrap();
+        assert_eq!(config.name, "notes");
+        assert_eq!(config.journal.dir, "journal");
+        assert_eq!(config.journal.template, "daily.md");
+    }
+
+    #[test]
+    fn test_parse_toml_journal_defaults() {
+        let config =
+            LibraryConfig::parse_toml("[library]\nname = \"x\"\n", Path::new(".")).unwrap();
+        assert_eq!(config.journal, JournalConfig::default());
+    }
+
+    #[test]
+    fn test_parse_toml_index_section() {
+        let content = "[index]\npath = \"README.md\"\ngroup_by = \"tag\"\n";
+        let config = LibraryConfig::parse_toml(content, Path::new(".")).unwrap();
+        assert_eq!(config.index.path, "README.md");
+        assert_eq!(config.index.group_by, "tag");
+        assert_eq!(config.journal, JournalConfig::default());
+    }
+
+    #[test]
+    fn test_parse_toml_lint_section() {
+        let content = r#"
+[lint]
+bare_url = false
+line_length = 80
+list_marker = "-"
+required_frontmatter = ["title", "status"]
+"#;
+        let config = LibraryConfig::parse_toml(content, Path::new(".")).unwrap();
+        assert!(!config.lint.bare_url);
+        assert!(config.lint.missing_h1);
+        assert_eq!(config.lint.line_length, 80);
+        assert_eq!(config.lint.list_marker, "-");
+        assert_eq!(config.lint.required_frontmatter, vec!["title", "status"]);
+    }
+
+    #[test]
+    fn test_parse_toml_walk_section() {
+        let content =
+            "[walk]\nthreads = 4\nexclude = [\"drafts/\", \"*.tmp.md\"]\ngitignore = false\nfollow_symlinks = false\nmax_depth = 2\n";
+        let config = LibraryConfig::parse_toml(content, Path::new(".")).unwrap();
+        assert_eq!(config.walk.threads, 4);
+        assert_eq!(config.walk.exclude, vec!["drafts/", "*.tmp.md"]);
+        assert!(!config.walk.gitignore);
+        assert!(!config.walk.follow_symlinks);
+        assert_eq!(config.walk.max_depth, 2);
+
+        let config = LibraryConfig::parse_toml("", Path::new(".")).unwrap();
+        assert_eq!(config.walk, WalkConfig::default());
+    }
+
+    #[test]
+    fn test_parse_toml_links_section() {
+        let content = "[links]\nentry_points = [\"docs/start.md\"]\n";
+        let config = LibraryConfig::parse_toml(content, Path::new(".")).unwrap();
+        assert_eq!(config.links.entry_points, vec!["docs/start.md"]);
+
+        let config = LibraryConfig::parse_toml("", Path::new(".")).unwrap();
+        assert_eq!(config.links, LinksConfig::default());
+    }
+
+    #[test]
+    fn test_parse_toml_rejects_invalid_lint_values() {
+        let content = "[lint]\nline_length = \"long\"\n";
+        let err = LibraryConfig::parse_toml(content, Path::new(".")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_to_toml_round_trip() {
+        let mut config = LibraryConfig::new("lib", PathBuf::from("."));
+        config.journal.dir = String::from("notes/daily");
+        config.lint.required_frontmatter = vec![String::from("status")];
+        config.walk.exclude = vec![String::from("node_modules/")];
+        config.walk.max_depth = 3;
+        let parsed = LibraryConfig::parse_toml(&config.to_toml(), Path::new(".")).unwrap();
+        assert_eq!(parsed.lint, config.lint);
+        assert_eq!(parsed.fmt, config.fmt);
+        assert_eq!(parsed.walk, config.walk);
+        assert_eq!(parsed.links, config.links);
+        assert_eq!(parsed.journal.dir, "notes/daily");
+        assert_eq!(parsed.name, "lib");
+    }
+
     #[test]
     fn test_to_toml_escapes_special_chars() {
         let config = LibraryConfig::new("test\"lib", PathBuf::from("."));
         let toml = config.to_toml();
         assert!(toml.contains("name = \"test\\\"lib\""));
+        let parsed = LibraryConfig::parse_toml(&toml, Path::new(".")).unwrap();
+        assert_eq!(parsed.name, "test\"lib");
     }
 }
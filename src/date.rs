@@ -0,0 +1,193 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Names of the days of the week, starting on Monday
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// A calendar date (proleptic Gregorian, no time zone)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Create a date, returning None if it does not exist in the calendar
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    /// Parse a date in `YYYY-MM-DD` format
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.trim().splitn(3, '-');
+        let year = parts.next()?;
+        let month = parts.next()?;
+        let day = parts.next()?;
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None;
+        }
+        Self::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+    }
+
+    /// Today's date in UTC
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Self::from_unix_timestamp(secs)
+    }
+
+    /// The UTC date of a Unix timestamp
+    pub fn from_unix_timestamp(secs: i64) -> Self {
+        Self::from_days(secs.div_euclid(86_400))
+    }
+
+    /// Convert a number of days since 1970-01-01 into a date
+    pub fn from_days(days: i64) -> Self {
+        // Algorithm from Howard Hinnant's "chrono-Compatible Low-Level Date Algorithms"
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+
+    /// Number of days since 1970-01-01
+    pub fn to_days(self) -> i64 {
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// The date `days` days after this one (or before, if negative)
+    pub fn add_days(self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// Day of the week, 0 = Monday through 6 = Sunday
+    pub fn weekday_index(self) -> usize {
+        // 1970-01-01 was a Thursday
+        (self.to_days() + 3).rem_euclid(7) as usize
+    }
+
+    /// Name of the day of the week
+    pub fn weekday(self) -> &'static str {
+        WEEKDAYS[self.weekday_index()]
+    }
+
+    /// The Monday starting the week that contains this date
+    pub fn week_start(self) -> Self {
+        self.add_days(-(self.weekday_index() as i64))
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM` (UTC)
+pub fn format_timestamp(secs: i64) -> String {
+    let minutes = secs.rem_euclid(86_400) / 60;
+    format!(
+        "{} {:02}:{:02}",
+        Date::from_unix_timestamp(secs),
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// Whether a year is a leap year
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Number of days in a month
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let date = Date::parse("2026-10-17").unwrap();
+        assert_eq!(date, Date::new(2026, 10, 17).unwrap());
+        assert_eq!(date.to_string(), "2026-10-17");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        assert_eq!(Date::parse("2026-02-30"), None);
+        assert_eq!(Date::parse("2026-13-01"), None);
+        assert_eq!(Date::parse("26-10-17"), None);
+        assert_eq!(Date::parse("not a date"), None);
+    }
+
+    #[test]
+    fn test_days_round_trip() {
+        assert_eq!(Date::from_days(0), Date::new(1970, 1, 1).unwrap());
+        assert_eq!(Date::new(2000, 3, 1).unwrap().to_days(), 11_017);
+        let date = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(Date::from_days(date.to_days()), date);
+    }
+
+    #[test]
+    fn test_add_days_crosses_boundaries() {
+        let date = Date::new(2025, 12, 31).unwrap();
+        assert_eq!(date.add_days(1), Date::new(2026, 1, 1).unwrap());
+        assert_eq!(date.add_days(-365), Date::new(2024, 12, 31).unwrap());
+    }
+
+    #[test]
+    fn test_weekday() {
+        let date = Date::new(2026, 10, 17).unwrap();
+        assert_eq!(date.weekday(), "Saturday");
+        assert_eq!(date.week_start(), Date::new(2026, 10, 12).unwrap());
+    }
+
+    #[test]
+    fn test_from_unix_timestamp() {
+        assert_eq!(
+            Date::from_unix_timestamp(1_700_000_000),
+            Date::new(2023, 11, 14).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13");
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+    }
+}
@@ -0,0 +1,253 @@
+/// A single step in a line-based edit script
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    /// Line `old` of the old text equals line `new` of the new text
+    Equal(usize, usize),
+    /// Line of the old text removed
+    Delete(usize),
+    /// Line of the new text inserted
+    Insert(usize),
+}
+
+/// Number of edits above which texts are diffed as one replaced block, bounding the
+/// memory of the search (which grows with the square of the number of edits)
+const MAX_EDIT_DISTANCE: usize = 2000;
+
+/// Compute a shortest edit script between two sequences of lines (Myers' algorithm)
+fn diff_lines(a: &[&str], b: &[&str]) -> Vec<Op> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    // Step d only reads diagonals -d..=d of the previous step, so only those are kept
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for d in 0..=max as isize {
+        if d as usize > MAX_EDIT_DISTANCE {
+            return replace_lines(a, b);
+        }
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Walk the trace backwards to recover the edit script
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let at = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+        // The search starts from the top-left corner
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+                k + 1
+            } else {
+                k - 1
+            };
+            (at(prev_k), at(prev_k) - prev_k)
+        };
+
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            ops.push(Op::Equal(x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(Op::Insert(prev_y as usize));
+            } else {
+                ops.push(Op::Delete(prev_x as usize));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    ops
+}
+
+/// An edit script keeping the common leading and trailing lines and replacing
+/// everything between them
+fn replace_lines(a: &[&str], b: &[&str]) -> Vec<Op> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_end, b_end) = (a.len() - suffix, b.len() - suffix);
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Equal(i, i)).collect();
+    ops.extend((prefix..a_end).map(Op::Delete));
+    ops.extend((prefix..b_end).map(Op::Insert));
+    ops.extend((0..suffix).map(|i| Op::Equal(a_end + i, b_end + i)));
+    ops
+}
+
+/// Render a unified diff between two texts, or None if they are identical
+pub fn unified_diff(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    context: usize,
+) -> Option<String> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&a, &b);
+    if ops.iter().all(|op| matches!(op, Op::Equal(..))) {
+        return None;
+    }
+
+    let mut output = format!("--- {}\n+++ {}\n", old_label, new_label);
+
+    // Group changes into hunks, merging those separated by at most 2 * context lines
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(..)))
+        .map(|(i, _)| i)
+        .collect();
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changes {
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    for (start, end) in hunks {
+        let hunk = &ops[start..end];
+        let (old_start, new_start) = position_before(&ops[..start]);
+        let old_len = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_len),
+            hunk_range(new_start, new_len)
+        ));
+        for op in hunk {
+            match *op {
+                Op::Equal(i, _) => output.push_str(&format!(" {}\n", a[i])),
+                Op::Delete(i) => output.push_str(&format!("-{}\n", a[i])),
+                Op::Insert(j) => output.push_str(&format!("+{}\n", b[j])),
+            }
+        }
+    }
+
+    Some(output)
+}
+
+/// Zero-based old and new line positions at the start of a hunk
+fn position_before(before: &[Op]) -> (usize, usize) {
+    let old = before
+        .iter()
+        .filter(|op| !matches!(op, Op::Insert(_)))
+        .count();
+    let new = before
+        .iter()
+        .filter(|op| !matches!(op, Op::Delete(_)))
+        .count();
+    (old, new)
+}
+
+/// Format a hunk range in unified diff notation (1-based, `start,len`)
+fn hunk_range(start: usize, len: usize) -> String {
+    if len == 0 {
+        format!("{},0", start)
+    } else if len == 1 {
+        format!("{}", start + 1)
+    } else {
+        format!("{},{}", start + 1, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_texts() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "old", "new", 3), None);
+    }
+
+    #[test]
+    fn test_single_change() {
+        let diff = unified_diff("a\nb\nc\n", "a\nB\nc\n", "old", "new", 3).unwrap();
+        assert_eq!(diff, "--- old\n+++ new\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n");
+    }
+
+    #[test]
+    fn test_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+        let new: String = (1..=20)
+            .map(|i| match i {
+                2 => String::from("two\n"),
+                19 => String::from("nineteen\n"),
+                _ => format!("{}\n", i),
+            })
+            .collect();
+        let diff = unified_diff(&old, &new, "old", "new", 1).unwrap();
+        assert!(diff.contains("@@ -1,3 +1,3 @@\n 1\n-2\n+two\n 3\n"));
+        assert!(diff.contains("@@ -18,3 +18,3 @@\n 18\n-19\n+nineteen\n 20\n"));
+    }
+
+    #[test]
+    fn test_large_rewrite_is_one_replaced_block() {
+        let old: String = (0..3000).map(|i| format!("old {}\n", i)).collect();
+        let new: String = (0..3000).map(|i| format!("new {}\n", i)).collect();
+        let old = format!("head\n{}tail\n", old);
+        let new = format!("head\n{}tail\n", new);
+        let diff = unified_diff(&old, &new, "old", "new", 1).unwrap();
+        assert!(diff.starts_with("--- old\n+++ new\n@@ -1,3002 +1,3002 @@\n head\n-old 0\n"));
+        assert!(diff.ends_with("+new 2999\n tail\n"));
+        assert_eq!(diff.matches("@@").count(), 2);
+    }
+
+    #[test]
+    fn test_replace_lines() {
+        assert_eq!(
+            replace_lines(&["a", "b", "c"], &["a", "x", "c"]),
+            vec![
+                Op::Equal(0, 0),
+                Op::Delete(1),
+                Op::Insert(1),
+                Op::Equal(2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_into_empty() {
+        let diff = unified_diff("", "a\n", "old", "new", 3).unwrap();
+        assert_eq!(diff, "--- old\n+++ new\n@@ -0,0 +1 @@\n+a\n");
+    }
+}
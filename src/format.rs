@@ -0,0 +1,866 @@
+use crate::config::FmtConfig;
+use crate::frontmatter;
+use crate::markdown::parse_heading;
+
+/// Column alignment of a table, from its delimiter row
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    None,
+    Left,
+    Right,
+    Center,
+}
+
+/// A list item line: its marker and where its content starts
+#[derive(Debug)]
+struct ListItem<'a> {
+    indent: usize,
+    marker: &'a str,
+    content_offset: usize,
+    content: &'a str,
+}
+
+/// An open list item that following lines may belong to
+#[derive(Debug)]
+struct ListLevel {
+    /// Content column of the item in the original document
+    offset: usize,
+    /// Content column of the item in the formatted document
+    new_offset: usize,
+}
+
+/// Format a markdown document.
+///
+/// Emphasis is written as `_em_` and `**strong**`, tables are aligned, list items use `-`
+/// and are indented to their parent's content, code fences use backticks and paragraphs
+/// are wrapped at `config.width` (0 keeps the existing line breaks). Frontmatter, code,
+/// HTML and block quotes are left as they are, and so are `\r\n` line endings. Formatting
+/// a formatted document changes nothing.
+pub fn format(content: &str, config: &FmtConfig) -> String {
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let body_start = frontmatter::body_start(&lines);
+
+    let mut formatter = Formatter {
+        lines: &lines[body_start..],
+        width: config.width,
+        output: Vec::new(),
+        lists: Vec::new(),
+    };
+    formatter.run();
+
+    let mut output: Vec<String> = lines[..body_start].iter().map(|l| l.to_string()).collect();
+    for line in formatter.output {
+        // Collapse runs of blank lines
+        if line.is_empty() && output.last().map(|l| l.is_empty()).unwrap_or(true) {
+            continue;
+        }
+        output.push(line);
+    }
+    while output.len() > body_start && output.last().map(|l| l.is_empty()).unwrap_or(false) {
+        output.pop();
+    }
+
+    let mut result = output.join(newline);
+    if !result.is_empty() {
+        result.push_str(newline);
+    }
+    result
+}
+
+/// Line-by-line formatting state
+struct Formatter<'a> {
+    lines: &'a [&'a str],
+    width: usize,
+    output: Vec<String>,
+    lists: Vec<ListLevel>,
+}
+
+impl<'a> Formatter<'a> {
+    fn run(&mut self) {
+        let mut i = 0;
+        while i < self.lines.len() {
+            i = self.block(i);
+        }
+    }
+
+    /// Format the block starting at line `i`, returning the index of the next block
+    fn block(&mut self, i: usize) -> usize {
+        let line = self.lines[i];
+        if line.trim().is_empty() {
+            self.output.push(String::new());
+            return i + 1;
+        }
+
+        let indent = indentation(line);
+        if let Some(item) = parse_list_item(line) {
+            let container = self.lists.last().map(|l| l.offset).unwrap_or(0);
+            if item.indent < container + 4 {
+                return self.list_item(i, item);
+            }
+        }
+
+        // Close list items this line is not indented enough to belong to
+        while self
+            .lists
+            .last()
+            .map(|l| l.offset > indent)
+            .unwrap_or(false)
+        {
+            self.lists.pop();
+        }
+        let (offset, new_offset) = self
+            .lists
+            .last()
+            .map(|l| (l.offset, l.new_offset))
+            .unwrap_or((0, 0));
+        let relative = strip_indent(line, offset);
+        let prefix = " ".repeat(new_offset);
+
+        if indentation(relative) >= 4 {
+            // Indented code
+            self.output.push(reindent(line, offset, new_offset));
+            return i + 1;
+        }
+        if let Some((fence_char, fence_len)) = fence_open(relative) {
+            return self.fenced_code(i, fence_char, fence_len, offset, new_offset);
+        }
+        if let Some(heading) = parse_heading(relative, i) {
+            let text = normalize_emphasis(&heading.text);
+            let heading = format!("{}{} {}", prefix, "#".repeat(heading.level), text);
+            self.output.push(heading.trim_end().to_string());
+            return i + 1;
+        }
+        if is_thematic_break(relative) {
+            self.output.push(format!("{}{}", prefix, relative.trim()));
+            return i + 1;
+        }
+        if relative.contains('|')
+            && self
+                .lines
+                .get(i + 1)
+                .map(|next| is_table_delimiter(strip_indent(next, offset)))
+                .unwrap_or(false)
+        {
+            return self.table(i, offset, &prefix);
+        }
+        if starts_verbatim_block(relative) {
+            // Block quotes, HTML and link reference definitions run until a blank line
+            let mut j = i;
+            while j < self.lines.len() && !self.lines[j].trim().is_empty() {
+                self.output.push(
+                    reindent(self.lines[j], offset, new_offset)
+                        .trim_end()
+                        .to_string(),
+                );
+                j += 1;
+            }
+            return j;
+        }
+
+        self.paragraph(i, relative.trim(), &prefix, &prefix)
+    }
+
+    /// Format a list item, indenting it to the content of its parent item
+    fn list_item(&mut self, i: usize, item: ListItem) -> usize {
+        while self
+            .lists
+            .last()
+            .map(|l| l.offset > item.indent)
+            .unwrap_or(false)
+        {
+            self.lists.pop();
+        }
+        let new_indent = self.lists.last().map(|l| l.new_offset).unwrap_or(0);
+        let marker = match item.marker {
+            "*" | "+" => "-",
+            marker => marker,
+        };
+        let new_offset = new_indent + marker.len() + 1;
+        self.lists.push(ListLevel {
+            offset: item.content_offset,
+            new_offset,
+        });
+
+        let first_prefix = format!("{}{} ", " ".repeat(new_indent), marker);
+        if item.content.is_empty() {
+            self.output.push(first_prefix.trim_end().to_string());
+            return i + 1;
+        }
+        self.paragraph(i, item.content, &first_prefix, &" ".repeat(new_offset))
+    }
+
+    /// Format a paragraph (or the text of a list item) starting at line `i`, whose
+    /// first line's text is `first`
+    fn paragraph(&mut self, i: usize, first: &str, first_prefix: &str, prefix: &str) -> usize {
+        let mut text_lines = vec![(first, hard_break(self.lines[i]))];
+        let mut j = i + 1;
+        while j < self.lines.len() && !interrupts_paragraph(self.lines[j]) {
+            if is_setext_underline(self.lines[j]) {
+                // A setext heading: keep its text and underline as they are
+                for (k, (text, _)) in text_lines.iter().enumerate() {
+                    let prefix = if k == 0 { first_prefix } else { prefix };
+                    self.output.push(format!("{}{}", prefix, text));
+                }
+                self.output
+                    .push(format!("{}{}", prefix, self.lines[j].trim()));
+                return j + 1;
+            }
+            text_lines.push((self.lines[j].trim(), hard_break(self.lines[j])));
+            j += 1;
+        }
+
+        // Hard line breaks split the paragraph into separately wrapped segments
+        let mut segments: Vec<(Vec<&str>, Option<&str>)> = vec![(Vec::new(), None)];
+        for (text, line_break) in text_lines {
+            let segment = segments.last_mut().expect("segments is never empty");
+            let text = match line_break {
+                Some("\\") => &text[..text.len() - 1],
+                _ => text,
+            };
+            segment.0.push(text.trim_end());
+            if line_break.is_some() {
+                segment.1 = line_break;
+                segments.push((Vec::new(), None));
+            }
+        }
+        segments.retain(|(lines, _)| !lines.is_empty());
+
+        let mut first_line = true;
+        for (lines, line_break) in segments {
+            let text = normalize_emphasis(&lines.join("\n"));
+            let mut wrapped = if self.width == 0 {
+                text.lines().map(|l| l.to_string()).collect()
+            } else {
+                let available = self.width.saturating_sub(prefix.chars().count());
+                wrap(&text, available)
+            };
+            if let (Some(last), Some(line_break)) = (wrapped.last_mut(), line_break) {
+                last.push_str(line_break);
+            }
+            for line in wrapped {
+                let prefix = if first_line { first_prefix } else { prefix };
+                self.output.push(format!("{}{}", prefix, line));
+                first_line = false;
+            }
+        }
+        j
+    }
+
+    /// Copy a fenced code block, switching `~~~` fences to backticks when the code does
+    /// not itself contain a backtick fence
+    fn fenced_code(
+        &mut self,
+        i: usize,
+        fence_char: char,
+        fence_len: usize,
+        offset: usize,
+        new_offset: usize,
+    ) -> usize {
+        let mut end = None;
+        for j in i + 1..self.lines.len() {
+            if is_fence_close(strip_indent(self.lines[j], offset), fence_char, fence_len) {
+                end = Some(j);
+                break;
+            }
+        }
+        let Some(end) = end else {
+            // An unclosed fence runs to the end of the document; leave it alone
+            for line in &self.lines[i..] {
+                self.output.push(reindent(line, offset, new_offset));
+            }
+            return self.lines.len();
+        };
+
+        let inner = &self.lines[i + 1..end];
+        let switch = fence_char == '~' && !inner.iter().any(|l| l.trim_start().starts_with("```"));
+        for (j, line) in self.lines[i..=end].iter().enumerate() {
+            let mut line = reindent(line, offset, new_offset);
+            if switch && (j == 0 || i + j == end) {
+                let fence = "~".repeat(fence_len);
+                line = line.replacen(&fence, "```", 1);
+            }
+            self.output.push(line);
+        }
+        end + 1
+    }
+
+    /// Align the columns of a table starting at line `i`
+    fn table(&mut self, i: usize, offset: usize, prefix: &str) -> usize {
+        let mut j = i;
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        while j < self.lines.len() {
+            let line = strip_indent(self.lines[j], offset);
+            if line.trim().is_empty() || (j > i + 1 && !line.contains('|')) {
+                break;
+            }
+            rows.push(split_row(line));
+            j += 1;
+        }
+
+        let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let aligns: Vec<Align> = (0..columns)
+            .map(|c| {
+                rows[1]
+                    .get(c)
+                    .map(|cell| parse_align(cell))
+                    .unwrap_or(Align::None)
+            })
+            .collect();
+        for (r, row) in rows.iter_mut().enumerate() {
+            row.resize(columns, String::new());
+            if r != 1 {
+                for cell in row.iter_mut() {
+                    *cell = normalize_emphasis(cell);
+                }
+            }
+        }
+        let widths: Vec<usize> = (0..columns)
+            .map(|c| {
+                rows.iter()
+                    .enumerate()
+                    .filter(|(r, _)| *r != 1)
+                    .map(|(_, row)| row[c].chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            })
+            .collect();
+
+        for (r, row) in rows.iter().enumerate() {
+            let cells: Vec<String> = (0..columns)
+                .map(|c| {
+                    if r == 1 {
+                        delimiter_cell(aligns[c], widths[c])
+                    } else {
+                        pad_cell(&row[c], aligns[c], widths[c])
+                    }
+                })
+                .collect();
+            self.output
+                .push(format!("{}| {} |", prefix, cells.join(" | ")));
+        }
+        j
+    }
+}
+
+/// Number of leading whitespace characters
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Remove up to `count` leading spaces
+fn strip_indent(line: &str, count: usize) -> &str {
+    let strip = indentation(line).min(count);
+    &line[strip..]
+}
+
+/// Move a line from a container at column `offset` to one at column `new_offset`
+fn reindent(line: &str, offset: usize, new_offset: usize) -> String {
+    if line.trim().is_empty() {
+        return String::new();
+    }
+    format!("{}{}", " ".repeat(new_offset), strip_indent(line, offset))
+}
+
+/// Parse a list item line
+fn parse_list_item(line: &str) -> Option<ListItem<'_>> {
+    let indent = indentation(line);
+    let rest = &line[indent..];
+    let marker_len = if rest.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+        if !(1..=9).contains(&digits) || !rest[digits..].starts_with(['.', ')']) {
+            return None;
+        }
+        digits + 1
+    };
+    let after = &rest[marker_len..];
+    if (!after.is_empty() && !after.starts_with([' ', '\t'])) || is_thematic_break(line) {
+        return None;
+    }
+    let content = after.trim();
+    let spaces = after.len() - after.trim_start().len();
+    let spaces = if content.is_empty() || spaces > 4 {
+        1
+    } else {
+        spaces
+    };
+    Some(ListItem {
+        indent,
+        marker: &rest[..marker_len],
+        content_offset: indent + marker_len + spaces,
+        content,
+    })
+}
+
+/// The fence character and length if a line opens a fenced code block
+fn fence_open(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start();
+    let fence_char = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = trimmed.chars().take_while(|&c| c == fence_char).count();
+    let info = &trimmed[len..];
+    if len < 3 || (fence_char == '`' && info.contains('`')) {
+        return None;
+    }
+    Some((fence_char, len))
+}
+
+/// Whether a line closes a fenced code block opened with the given fence
+fn is_fence_close(line: &str, fence_char: char, fence_len: usize) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() >= fence_len && trimmed.chars().all(|c| c == fence_char)
+}
+
+/// Whether a line is a thematic break (`---`, `* * *`, `___`)
+fn is_thematic_break(line: &str) -> bool {
+    let trimmed = line.trim();
+    let Some(first) = trimmed
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '-' | '*' | '_'))
+    else {
+        return false;
+    };
+    trimmed.chars().all(|c| c == first || c == ' ' || c == '\t')
+        && trimmed.chars().filter(|&c| c == first).count() >= 3
+}
+
+/// Whether a line is a setext heading underline (`===` or `---`)
+fn is_setext_underline(line: &str) -> bool {
+    let trimmed = line.trim();
+    indentation(line) <= 3
+        && !trimmed.is_empty()
+        && (trimmed.chars().all(|c| c == '=') || trimmed.chars().all(|c| c == '-'))
+}
+
+/// Whether a line starts a block that is copied verbatim
+fn starts_verbatim_block(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    let link_definition = trimmed.starts_with('[')
+        && trimmed
+            .find("]:")
+            .map(|idx| !trimmed[..idx].contains(']'))
+            .unwrap_or(false);
+    trimmed.starts_with('>') || trimmed.starts_with('<') || link_definition
+}
+
+/// Whether a line ends the paragraph before it rather than continuing it
+fn interrupts_paragraph(line: &str) -> bool {
+    let relative = line.trim_start();
+    line.trim().is_empty()
+        || parse_list_item(line).is_some()
+        || (indentation(line) <= 3 && parse_heading(line, 0).is_some())
+        || fence_open(line).is_some()
+        || (is_thematic_break(line) && !is_setext_underline(line))
+        || relative.starts_with('>')
+        || relative.starts_with('<')
+}
+
+/// The hard line break (two spaces or a backslash) a line ends with, if any
+fn hard_break(line: &str) -> Option<&'static str> {
+    if line.ends_with("  ") && !line.trim().is_empty() {
+        Some("  ")
+    } else if line.ends_with('\\') && !line.ends_with("\\\\") {
+        Some("\\")
+    } else {
+        None
+    }
+}
+
+/// Whether a line is a table delimiter row (`| --- | :-: |`)
+fn is_table_delimiter(line: &str) -> bool {
+    let cells = split_row(line);
+    !cells.is_empty()
+        && line.contains('-')
+        && cells.iter().all(|cell| {
+            let inner = cell.trim_start_matches(':').trim_end_matches(':');
+            !inner.is_empty() && inner.chars().all(|c| c == '-')
+        })
+}
+
+/// Split a table row into trimmed cells, ignoring escaped pipes and pipes in code spans
+fn split_row(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = match trimmed.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => trimmed,
+    };
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut in_code = false;
+    let mut escaped = false;
+    for c in trimmed.chars() {
+        match c {
+            '|' if !escaped && !in_code => cells.push(std::mem::take(&mut cell)),
+            c => {
+                if c == '`' && !escaped {
+                    in_code = !in_code;
+                }
+                escaped = c == '\\' && !escaped;
+                cell.push(c);
+            }
+        }
+    }
+    cells.push(cell);
+    cells.into_iter().map(|c| c.trim().to_string()).collect()
+}
+
+/// Alignment of a delimiter row cell
+fn parse_align(cell: &str) -> Align {
+    match (cell.starts_with(':'), cell.ends_with(':')) {
+        (true, true) => Align::Center,
+        (true, false) => Align::Left,
+        (false, true) => Align::Right,
+        (false, false) => Align::None,
+    }
+}
+
+/// A delimiter row cell of the given width
+fn delimiter_cell(align: Align, width: usize) -> String {
+    match align {
+        Align::None => "-".repeat(width),
+        Align::Left => format!(":{}", "-".repeat(width - 1)),
+        Align::Right => format!("{}:", "-".repeat(width - 1)),
+        Align::Center => format!(":{}:", "-".repeat(width - 2)),
+    }
+}
+
+/// Pad a cell to the column width according to its alignment
+fn pad_cell(cell: &str, align: Align, width: usize) -> String {
+    let padding = width - cell.chars().count();
+    match align {
+        Align::None | Align::Left => format!("{}{}", cell, " ".repeat(padding)),
+        Align::Right => format!("{}{}", " ".repeat(padding), cell),
+        Align::Center => format!(
+            "{}{}{}",
+            " ".repeat(padding / 2),
+            cell,
+            " ".repeat(padding - padding / 2)
+        ),
+    }
+}
+
+/// Split text into words at whitespace outside code spans, so code spans are never
+/// broken across lines or have their spacing changed
+fn words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '`' {
+            let run = run_length(&chars, i);
+            if let Some(close) = find_code_span_end(&chars, i, run) {
+                let span: String = chars[i..close + run].iter().collect();
+                word.push_str(&span.replace('\n', " "));
+                i = close + run;
+                continue;
+            }
+            word.extend(&chars[i..i + run]);
+            i += run;
+        } else if c.is_whitespace() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            i += 1;
+        } else {
+            word.push(c);
+            i += 1;
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Whether a word would start a different block if it began a line
+fn can_start_line(word: &str) -> bool {
+    let block_marker = matches!(word, "-" | "+" | "*" | ">" | "=" | "|")
+        || word.starts_with('#')
+        || word.starts_with('>')
+        || word.starts_with("```")
+        || word.starts_with("~~~")
+        || word.starts_with('<')
+        || word.chars().all(|c| c == '-' || c == '=')
+        || parse_list_item(&format!("{} x", word)).is_some();
+    !block_marker
+}
+
+/// Wrap text greedily to the given width
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in words(text) {
+        let fits = line.chars().count() + 1 + word.chars().count() <= width;
+        if line.is_empty() || fits || !can_start_line(&word) {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut line, word));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Length of the run of identical characters starting at `i`
+fn run_length(chars: &[char], i: usize) -> usize {
+    chars[i..].iter().take_while(|&&c| c == chars[i]).count()
+}
+
+/// Index of the backtick run closing a code span opened by `run` backticks at `start`
+fn find_code_span_end(chars: &[char], start: usize, run: usize) -> Option<usize> {
+    let mut j = start + run;
+    while j < chars.len() {
+        if chars[j] == '`' {
+            let len = run_length(chars, j);
+            if len == run {
+                return Some(j);
+            }
+            j += len;
+        } else {
+            j += 1;
+        }
+    }
+    None
+}
+
+/// Index just past a region that emphasis must not be changed in (code spans, link
+/// destinations and autolinks) starting at `i`, if one starts there
+fn skip_literal(chars: &[char], i: usize) -> Option<usize> {
+    match chars[i] {
+        '\\' => Some((i + 2).min(chars.len())),
+        '`' => {
+            let run = run_length(chars, i);
+            Some(
+                find_code_span_end(chars, i, run)
+                    .map(|end| end + run)
+                    .unwrap_or(i + run),
+            )
+        }
+        '(' if i > 0 && chars[i - 1] == ']' => chars[i..]
+            .iter()
+            .position(|&c| c == ')')
+            .map(|end| i + end + 1),
+        '<' => chars[i..]
+            .iter()
+            .position(|&c| c == '>' || c.is_whitespace())
+            .filter(|&end| chars[i + end] == '>')
+            .map(|end| i + end + 1),
+        _ => None,
+    }
+}
+
+/// Write emphasis as `_em_` and strong emphasis as `**strong**`
+fn normalize_emphasis(text: &str) -> String {
+    let mut chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some(next) = skip_literal(&chars, i) {
+            i = next;
+            continue;
+        }
+        let c = chars[i];
+        if c != '*' && c != '_' {
+            i += 1;
+            continue;
+        }
+        let run = run_length(&chars, i);
+        let (from, to) = match (c, run) {
+            ('*', 1) => ('*', '_'),
+            ('_', 2) => ('_', '*'),
+            _ => {
+                i += run;
+                continue;
+            }
+        };
+        if let Some(close) = find_emphasis_close(&chars, i, run) {
+            // The other delimiter inside would change how the result is parsed
+            if !chars[i + run..close].contains(&to) {
+                for k in (i..i + run).chain(close..close + run) {
+                    chars[k] = if chars[k] == from { to } else { chars[k] };
+                }
+                i = close + run;
+                continue;
+            }
+        }
+        i += run;
+    }
+    chars.into_iter().collect()
+}
+
+/// Find the delimiter run closing emphasis opened by `run` delimiters at `start`.
+///
+/// Only emphasis that starts and ends at word boundaries is considered, as `_` does not
+/// work inside words.
+fn find_emphasis_close(chars: &[char], start: usize, run: usize) -> Option<usize> {
+    let delimiter = chars[start];
+    let opens = start
+        .checked_sub(1)
+        .map(|p| !chars[p].is_alphanumeric())
+        .unwrap_or(true)
+        && chars
+            .get(start + run)
+            .map(|c| !c.is_whitespace())
+            .unwrap_or(false);
+    if !opens {
+        return None;
+    }
+
+    let mut j = start + run;
+    while j < chars.len() {
+        if let Some(next) = skip_literal(chars, j) {
+            j = next;
+            continue;
+        }
+        if chars[j] != delimiter {
+            j += 1;
+            continue;
+        }
+        let len = run_length(chars, j);
+        let closes = !chars[j - 1].is_whitespace()
+            && chars
+                .get(j + len)
+                .map(|c| !c.is_alphanumeric())
+                .unwrap_or(true);
+        if len == run && closes {
+            return Some(j);
+        }
+        j += len;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(content: &str, width: usize) -> String {
+        format(content, &FmtConfig { width })
+    }
+
+    #[test]
+    fn test_normalize_emphasis() {
+        assert_eq!(
+            normalize_emphasis("*em* and __strong__ and **kept** _kept_"),
+            "_em_ and **strong** and **kept** _kept_"
+        );
+        // Intra-word, code, links and unmatched delimiters are left alone
+        assert_eq!(normalize_emphasis("a*b*c"), "a*b*c");
+        assert_eq!(
+            normalize_emphasis("`*x*` [*a*](u_*b*_) "),
+            "`*x*` [_a_](u_*b*_) "
+        );
+        assert_eq!(normalize_emphasis("2 * 3 * 4"), "2 * 3 * 4");
+        assert_eq!(normalize_emphasis("*snake_case*"), "*snake_case*");
+    }
+
+    #[test]
+    fn test_wrap_paragraphs() {
+        let content =
+            "# Title\n\nThe quick brown fox jumps over\nthe lazy dog and keeps running far away.\n";
+        assert_eq!(
+            fmt(content, 30),
+            "# Title\n\nThe quick brown fox jumps over\nthe lazy dog and keeps running\nfar away.\n"
+        );
+        // Width 0 keeps line breaks
+        assert_eq!(fmt(content, 0), content);
+    }
+
+    #[test]
+    fn test_wrap_never_starts_a_block() {
+        assert_eq!(
+            fmt("aaaa - bbbb # cc 1. dd\n", 6),
+            "aaaa -\nbbbb #\ncc 1.\ndd\n"
+        );
+    }
+
+    #[test]
+    fn test_wrap_keeps_code_spans_and_hard_breaks() {
+        let content = "one `a  b c` two  \nthree four\\\nfive\n";
+        assert_eq!(
+            fmt(content, 8),
+            "one\n`a  b c`\ntwo  \nthree\nfour\\\nfive\n"
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        let content = "* one\n    * nested item\n      continued\n+ two\n\n1. first\n   - sub\n";
+        assert_eq!(
+            fmt(content, 80),
+            "- one\n  - nested item continued\n- two\n\n1. first\n   - sub\n"
+        );
+    }
+
+    #[test]
+    fn test_list_item_paragraphs_and_code() {
+        let content = "*   item\n\n    more text\n\n    ```\n    code\n    ```\n";
+        assert_eq!(
+            fmt(content, 80),
+            "- item\n\n  more text\n\n  ```\n  code\n  ```\n"
+        );
+    }
+
+    #[test]
+    fn test_tables() {
+        let content = "| Name | Qty |\n|:-|--:|\n| apple | 3 |\n| kiwi *fruit* | 12 |\n";
+        assert_eq!(
+            fmt(content, 80),
+            "| Name         | Qty |\n| :----------- | --: |\n| apple        |   3 |\n| kiwi _fruit_ |  12 |\n"
+        );
+    }
+
+    #[test]
+    fn test_code_fences() {
+        assert_eq!(
+            fmt("~~~rust\nlet x  =  *y*;\n~~~\n", 80),
+            "```rust\nlet x  =  *y*;\n```\n"
+        );
+        // A tilde fence containing a backtick fence is kept
+        let nested = "~~~\n```\n~~~\n";
+        assert_eq!(fmt(nested, 80), nested);
+    }
+
+    #[test]
+    fn test_preserved_blocks() {
+        let content = "---\ntitle:  x\n---\n# T\n\n> quoted *text* that is long\n\n<div>\n*html*\n</div>\n\nSetext\n------\n\n    indented   code\n";
+        assert_eq!(fmt(content, 10), content);
+    }
+
+    #[test]
+    fn test_blank_lines_and_headings() {
+        assert_eq!(
+            fmt("#   Title  ##\n\n\n\nText\n\n\n", 80),
+            "# Title\n\nText\n"
+        );
+    }
+
+    #[test]
+    fn test_keeps_crlf_line_endings() {
+        let formatted = fmt("#   Title\r\n\r\n* *a*\r\n* b\r\n", 80);
+        assert_eq!(formatted, "# Title\r\n\r\n- _a_\r\n- b\r\n");
+        assert_eq!(fmt(&formatted, 80), formatted);
+    }
+
+    #[test]
+    fn test_idempotent() {
+        let content = "# Doc\n\nSome *text* with a [link](http://example.com/a_b) that should wrap around nicely.\n\n* a\n    * b\n\n| a | b |\n|---|---|\n| long cell | x |\n\n~~~\ncode\n~~~\n";
+        let once = fmt(content, 40);
+        assert_eq!(fmt(&once, 40), once);
+    }
+}
@@ -0,0 +1,483 @@
+/// Frontmatter delimiter line
+const DELIMITER: &str = "---";
+
+/// Characters that cannot start a plain YAML scalar
+const INDICATORS: &[char] = &['&', '*', '!', '|', '>', '%', '@', '`', '#'];
+
+/// Locate the frontmatter block, returning the line indices of the opening and closing delimiters
+fn block_bounds<S: AsRef<str>>(lines: &[S]) -> Option<(usize, usize)> {
+    if lines.first().map(|l| l.as_ref().trim_end()) != Some(DELIMITER) {
+        return None;
+    }
+    let end = lines[1..]
+        .iter()
+        .position(|l| matches!(l.as_ref().trim_end(), "---" | "..."))?;
+    Some((0, end + 1))
+}
+
+/// Index of the first line after the frontmatter block (0 if there is none)
+pub fn body_start<S: AsRef<str>>(lines: &[S]) -> usize {
+    block_bounds(lines).map(|(_, end)| end + 1).unwrap_or(0)
+}
+
+/// Split a top-level `key: value` line into its key and raw value
+fn split_key(line: &str) -> Option<(&str, &str)> {
+    if line.starts_with(char::is_whitespace) || line.starts_with('#') || line.starts_with('-') {
+        return None;
+    }
+    let (key, value) = line.split_once(':')?;
+    Some((key.trim(), value))
+}
+
+/// Split a raw value into the value itself and any trailing ` # comment`
+fn split_comment(raw: &str) -> (&str, &str) {
+    let mut in_quote: Option<char> = None;
+    let mut escaped = false;
+    let mut prev_whitespace = true;
+    for (idx, c) in raw.char_indices() {
+        match in_quote {
+            Some(_) if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => in_quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => in_quote = Some(c),
+            None if c == '#' && prev_whitespace => return (&raw[..idx], &raw[idx..]),
+            None => {}
+        }
+        prev_whitespace = c.is_whitespace();
+    }
+    (raw, "")
+}
+
+/// Remove matching surrounding quotes from a scalar value, resolving the escapes written
+/// by `quote` (`\\`, `\"`) in double quotes and doubled quotes (`''`) in single quotes
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].replace("''", "'");
+    }
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        let mut result = String::new();
+        let mut chars = value[1..value.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next() {
+                    Some(escaped @ ('\\' | '"')) => result.push(escaped),
+                    Some(other) => {
+                        result.push(c);
+                        result.push(other);
+                    }
+                    None => result.push(c),
+                }
+            } else {
+                result.push(c);
+            }
+        }
+        return result;
+    }
+    value.to_string()
+}
+
+/// Quote a value if it would not survive as a plain YAML scalar
+pub fn format_scalar(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.trim() != value
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.ends_with(':')
+        || value.starts_with(INDICATORS);
+    if needs_quotes {
+        quote(value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format an item of a flow sequence (`[a, b]`), quoting it if necessary
+fn format_flow_item(value: &str) -> String {
+    if value.contains([',', '[', ']', '{', '}']) || value.starts_with(['"', '\'']) {
+        quote(value)
+    } else {
+        format_scalar(value)
+    }
+}
+
+/// Wrap a value in double quotes, escaping as needed
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Find a top-level key in the frontmatter block, returning the index of its line and
+/// the end (exclusive) of any indented or `- ` continuation lines that belong to it
+fn find_entry<S: AsRef<str>>(lines: &[S], key: &str) -> Option<(usize, usize)> {
+    let (start, end) = block_bounds(lines)?;
+    let idx = (start + 1..end).find(|&i| {
+        split_key(lines[i].as_ref())
+            .map(|(k, _)| k == key)
+            .unwrap_or(false)
+    })?;
+    let continuation_end = (idx + 1..end)
+        .find(|&i| {
+            let line = lines[i].as_ref();
+            line.trim().is_empty()
+                || !(line.starts_with(char::is_whitespace) || line.starts_with('-'))
+        })
+        .unwrap_or(end);
+    Some((idx, continuation_end))
+}
+
+/// Split content into owned lines, remembering whether it ended with a newline
+fn to_lines(content: &str) -> (Vec<String>, bool) {
+    (
+        content.lines().map(|l| l.to_string()).collect(),
+        content.ends_with('\n'),
+    )
+}
+
+/// Join lines back together, preserving the original trailing newline behavior
+fn from_lines(lines: Vec<String>, trailing_newline: bool) -> String {
+    let mut result = lines.join("\n");
+    if trailing_newline {
+        result.push('\n');
+    }
+    result
+}
+
+/// Get the scalar value of a top-level frontmatter key.
+///
+/// Returns None if the key is missing or holds a nested block rather than a scalar.
+pub fn get(content: &str, key: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let (idx, continuation_end) = find_entry(&lines, key)?;
+    let (_, raw) = split_key(lines[idx])?;
+    let (value, _) = split_comment(raw);
+    if value.trim().is_empty() && continuation_end > idx + 1 {
+        return None;
+    }
+    Some(unquote(value))
+}
+
+/// Get the items of a top-level list key, written either as a flow sequence
+/// (`tags: [a, b]`), a block sequence (`- a` lines) or a single scalar
+pub fn get_list(content: &str, key: &str) -> Option<Vec<String>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let (idx, continuation_end) = find_entry(&lines, key)?;
+    let (_, raw) = split_key(lines[idx])?;
+    let (value, _) = split_comment(raw);
+    let value = value.trim();
+
+    let items = if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        split_flow_items(inner).into_iter().map(unquote).collect()
+    } else if value.is_empty() {
+        lines[idx + 1..continuation_end]
+            .iter()
+            .filter_map(|line| line.trim().strip_prefix('-'))
+            .map(|item| unquote(split_comment(item).0))
+            .collect()
+    } else {
+        vec![unquote(value)]
+    };
+    Some(items)
+}
+
+/// Split the inside of a flow sequence on commas outside quotes
+fn split_flow_items(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut in_quote: Option<char> = None;
+    let mut escaped = false;
+    let mut item_start = 0;
+    for (idx, c) in inner.char_indices() {
+        match in_quote {
+            Some(_) if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => in_quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => in_quote = Some(c),
+            None if c == ',' => {
+                items.push(inner[item_start..idx].trim());
+                item_start = idx + 1;
+            }
+            None => {}
+        }
+    }
+    items.push(inner[item_start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Set a top-level frontmatter key, replacing its value in place (keeping any trailing
+/// comment) or appending it to the block. A frontmatter block is created if none exists.
+///
+/// Frontmatter is edited line by line rather than re-serialized, so the order, comments
+/// and formatting of untouched lines are preserved.
+pub fn set(content: &str, key: &str, value: &str) -> String {
+    let (mut lines, trailing_newline) = to_lines(content);
+
+    match find_entry(&lines, key) {
+        Some((idx, continuation_end)) => {
+            let (_, raw) = split_key(&lines[idx]).unwrap_or_default();
+            let (_, comment) = split_comment(raw);
+            lines[idx] = if comment.is_empty() {
+                format!("{}: {}", key, value)
+            } else {
+                format!("{}: {} {}", key, value, comment)
+            };
+            // A nested block value is replaced by the new scalar
+            lines.drain(idx + 1..continuation_end);
+        }
+        None => match block_bounds(&lines) {
+            Some((_, end)) => lines.insert(end, format!("{}: {}", key, value)),
+            None => {
+                lines.insert(0, DELIMITER.to_string());
+                lines.insert(1, format!("{}: {}", key, value));
+                lines.insert(2, DELIMITER.to_string());
+            }
+        },
+    }
+
+    from_lines(lines, trailing_newline)
+}
+
+/// Remove a top-level key (and any nested value) from the frontmatter
+pub fn unset(content: &str, key: &str) -> String {
+    let (mut lines, trailing_newline) = to_lines(content);
+    match find_entry(&lines, key) {
+        Some((idx, continuation_end)) => {
+            lines.drain(idx..continuation_end);
+            from_lines(lines, trailing_newline)
+        }
+        None => content.to_string(),
+    }
+}
+
+/// Line indices of the `- item` lines of a block sequence value
+fn block_items(lines: &[String], idx: usize, continuation_end: usize) -> Vec<usize> {
+    (idx + 1..continuation_end)
+        .filter(|&i| lines[i].trim_start().starts_with('-'))
+        .collect()
+}
+
+/// Add an item to a list key, keeping the list's existing style. Does nothing if the
+/// item is already present; a missing key is created as a flow sequence.
+pub fn add_list_item(content: &str, key: &str, item: &str) -> String {
+    let Some(items) = get_list(content, key) else {
+        return set(content, key, &format!("[{}]", format_flow_item(item)));
+    };
+    if items.iter().any(|i| i == item) {
+        return content.to_string();
+    }
+
+    let (mut lines, trailing_newline) = to_lines(content);
+    let Some((idx, continuation_end)) = find_entry(&lines, key) else {
+        return content.to_string();
+    };
+
+    match block_items(&lines, idx, continuation_end).last() {
+        Some(&last) => {
+            // Block sequence: add a `- item` line indented like the last one
+            let indent: String = lines[last]
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .collect();
+            lines.insert(last + 1, format!("{}- {}", indent, format_scalar(item)));
+            from_lines(lines, trailing_newline)
+        }
+        None => {
+            let mut items: Vec<String> = items.iter().map(|i| format_flow_item(i)).collect();
+            items.push(format_flow_item(item));
+            set(content, key, &format!("[{}]", items.join(", ")))
+        }
+    }
+}
+
+/// Remove an item from a list key, keeping the list's existing style
+pub fn remove_list_item(content: &str, key: &str, item: &str) -> String {
+    let Some(items) = get_list(content, key) else {
+        return content.to_string();
+    };
+    if !items.iter().any(|i| i == item) {
+        return content.to_string();
+    }
+
+    let (mut lines, trailing_newline) = to_lines(content);
+    let Some((idx, continuation_end)) = find_entry(&lines, key) else {
+        return content.to_string();
+    };
+    let block_items = block_items(&lines, idx, continuation_end);
+
+    if block_items.is_empty() {
+        let remaining: Vec<String> = items
+            .iter()
+            .filter(|i| *i != item)
+            .map(|i| format_flow_item(i))
+            .collect();
+        return set(content, key, &format!("[{}]", remaining.join(", ")));
+    }
+
+    let matching: Vec<usize> = block_items
+        .iter()
+        .copied()
+        .filter(|&i| {
+            let text = lines[i].trim_start().trim_start_matches('-');
+            unquote(split_comment(text).0) == item
+        })
+        .collect();
+    if matching.len() == block_items.len() {
+        // Nothing left: collapse to an empty flow sequence
+        lines.drain(idx + 1..continuation_end);
+        lines[idx] = format!("{}: []", key);
+    } else {
+        for i in matching.into_iter().rev() {
+            lines.remove(i);
+        }
+    }
+    from_lines(lines, trailing_newline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_start() {
+        assert_eq!(body_start(&["---", "a: b", "---", "# Doc"]), 3);
+        assert_eq!(body_start(&["# Doc", "---"]), 0);
+    }
+
+    #[test]
+    fn test_get() {
+        let content = "---\ntitle: \"Hello\" # the title\nupdated: 2026-01-01\n---\n# Doc\n";
+        assert_eq!(get(content, "title"), Some("Hello".to_string()));
+        assert_eq!(get(content, "updated"), Some("2026-01-01".to_string()));
+        assert_eq!(get(content, "missing"), None);
+        assert_eq!(get("# No frontmatter\n", "title"), None);
+        assert_eq!(get("---\ntitle: unterminated\n", "title"), None);
+    }
+
+    #[test]
+    fn test_set_get_round_trip() {
+        let value = r#"Say: "hi" \ bye"#;
+        let content = set("# Doc\n", "title", value);
+        assert_eq!(get(&content, "title"), Some(value.to_string()));
+        // Setting what was read does not add escapes
+        let again = set(&content, "title", &get(&content, "title").unwrap());
+        assert_eq!(again, content);
+
+        let content = "---\ntitle: 'It''s # here' # comment\ntags: [\"a, \\\"b\\\"\", c]\n---\n";
+        assert_eq!(get(content, "title"), Some("It's # here".to_string()));
+        assert_eq!(
+            get_list(content, "tags"),
+            Some(vec![String::from("a, \"b\""), String::from("c")])
+        );
+    }
+
+    #[test]
+    fn test_set_replaces_in_place() {
+        let content = "---\n# comment\nupdated: 2026-01-01 # auto\ntags: [a]\n---\nBody\n";
+        let result = set(content, "updated", "2026-10-17");
+        assert_eq!(
+            result,
+            "---\n# comment\nupdated: 2026-10-17 # auto\ntags: [a]\n---\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_set_appends_and_creates() {
+        let result = set("---\ntitle: x\n---\nBody", "status", "draft");
+        assert_eq!(result, "---\ntitle: x\nstatus: draft\n---\nBody");
+
+        let result = set("# Doc\n", "status", "draft");
+        assert_eq!(result, "---\nstatus: draft\n---\n# Doc\n");
+    }
+
+    #[test]
+    fn test_set_replaces_block_value() {
+        let content = "---\ntags:\n  - a\n  - b\nstatus: draft\n---\n";
+        assert_eq!(
+            set(content, "tags", "[c]"),
+            "---\ntags: [c]\nstatus: draft\n---\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_keys_are_ignored() {
+        let content = "---\nauthor:\n  name: x\n---\n";
+        assert_eq!(get(content, "name"), None);
+        assert_eq!(get(content, "author"), None);
+    }
+
+    #[test]
+    fn test_unset() {
+        let content = "---\ntitle: x\ntags:\n- a\n- b\n# keep me\nstatus: done\n---\nBody\n";
+        assert_eq!(
+            unset(content, "tags"),
+            "---\ntitle: x\n# keep me\nstatus: done\n---\nBody\n"
+        );
+        assert_eq!(unset(content, "missing"), content);
+    }
+
+    #[test]
+    fn test_get_list_styles() {
+        let flow = "---\ntags: [rust, \"a, b\"] # note\n---\n";
+        assert_eq!(
+            get_list(flow, "tags"),
+            Some(vec!["rust".to_string(), "a, b".to_string()])
+        );
+
+        let block = "---\ntags:\n  - rust\n  - 'cli'\n---\n";
+        assert_eq!(
+            get_list(block, "tags"),
+            Some(vec!["rust".to_string(), "cli".to_string()])
+        );
+
+        let scalar = "---\ntags: rust\n---\n";
+        assert_eq!(get_list(scalar, "tags"), Some(vec!["rust".to_string()]));
+        assert_eq!(get_list(scalar, "missing"), None);
+    }
+
+    #[test]
+    fn test_add_list_item() {
+        let flow = "---\ntags: [a] # topics\n---\n";
+        assert_eq!(
+            add_list_item(flow, "tags", "b"),
+            "---\ntags: [a, b] # topics\n---\n"
+        );
+        assert_eq!(add_list_item(flow, "tags", "a"), flow);
+
+        let block = "---\ntags:\n  - a\ntitle: x\n---\n";
+        assert_eq!(
+            add_list_item(block, "tags", "b"),
+            "---\ntags:\n  - a\n  - b\ntitle: x\n---\n"
+        );
+
+        assert_eq!(
+            add_list_item("# Doc\n", "tags", "new"),
+            "---\ntags: [new]\n---\n# Doc\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_list_item() {
+        let flow = "---\ntags: [a, b]\n---\n";
+        assert_eq!(remove_list_item(flow, "tags", "a"), "---\ntags: [b]\n---\n");
+
+        let block = "---\ntags:\n  - a\n  - b\n---\n";
+        assert_eq!(
+            remove_list_item(block, "tags", "b"),
+            "---\ntags:\n  - a\n---\n"
+        );
+        assert_eq!(
+            remove_list_item("---\ntags:\n  - a\n---\n", "tags", "a"),
+            "---\ntags: []\n---\n"
+        );
+        assert_eq!(remove_list_item(flow, "tags", "zzz"), flow);
+    }
+
+    #[test]
+    fn test_format_scalar() {
+        assert_eq!(format_scalar("draft"), "draft");
+        assert_eq!(format_scalar("a: b"), "\"a: b\"");
+        assert_eq!(format_scalar(""), "\"\"");
+        assert_eq!(format_scalar("*bold"), "\"*bold\"");
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Field separator used in `git log` format strings
+const FIELD_SEP: char = '\x1f';
+
+/// Record separator used in `git log` format strings
+const RECORD_SEP: char = '\x1e';
+
+/// A commit that touched a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub hash: String,
+    pub author: String,
+    /// Author time as a Unix timestamp
+    pub timestamp: i64,
+    pub subject: String,
+}
+
+impl Commit {
+    /// Abbreviated commit hash
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(8)]
+    }
+}
+
+/// Run git in `dir` and return its standard output
+fn git(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Whether `dir` is inside a git work tree
+pub fn is_repository(dir: &Path) -> bool {
+    git(dir, &["rev-parse", "--is-inside-work-tree"])
+        .map(|out| out.trim() == "true")
+        .unwrap_or(false)
+}
+
+/// Commits that touched a file, newest first (following renames)
+pub fn file_history(dir: &Path, path: &Path) -> io::Result<Vec<Commit>> {
+    let format = format!("--format=%H{0}%an{0}%at{0}%s", FIELD_SEP);
+    let path = path.to_string_lossy();
+    let output = git(dir, &["log", "--follow", &format, "--", &path])?;
+    Ok(output.lines().filter_map(parse_commit).collect())
+}
+
+/// Parse a line produced by the `file_history` format string
+fn parse_commit(line: &str) -> Option<Commit> {
+    let mut fields = line.splitn(4, FIELD_SEP);
+    Some(Commit {
+        hash: fields.next()?.to_string(),
+        author: fields.next()?.to_string(),
+        timestamp: fields.next()?.parse().ok()?,
+        subject: fields.next().unwrap_or("").to_string(),
+    })
+}
+
+/// Distinct authors of a history, in order of their first contribution
+pub fn authors(history: &[Commit]) -> Vec<String> {
+    let mut authors: Vec<String> = Vec::new();
+    for commit in history.iter().rev() {
+        if !authors.contains(&commit.author) {
+            authors.push(commit.author.clone());
+        }
+    }
+    authors
+}
+
+/// Times of the first and last commits touching each file, keyed by path relative to
+/// the directory they were read in
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CommitTimes {
+    /// Time of the first commit adding each file
+    pub first: HashMap<String, i64>,
+    /// Time of the last commit touching each file
+    pub last: HashMap<String, i64>,
+}
+
+/// First and last commit times of every file below `dir`, from a single `git log`
+pub fn commit_times(dir: &Path) -> io::Result<CommitTimes> {
+    let format = format!("--format={}%at", RECORD_SEP);
+    // -z separates file names with NUL and leaves non-ASCII names unquoted
+    let output = git(
+        dir,
+        &["log", "--relative", "--name-only", "-z", &format, "--", "."],
+    )?;
+    Ok(parse_commit_times(&output))
+}
+
+/// Parse `git log --name-only -z` output into the first and last commit time per file
+fn parse_commit_times(output: &str) -> CommitTimes {
+    let mut times = CommitTimes::default();
+    for record in output.split(RECORD_SEP).filter(|r| !r.trim().is_empty()) {
+        let mut fields = record.split('\0');
+        let Some(timestamp) = fields.next().and_then(|t| t.trim().parse::<i64>().ok()) else {
+            continue;
+        };
+        for file in fields
+            .map(|f| f.trim_start_matches('\n'))
+            .filter(|f| !f.is_empty())
+        {
+            // Log output is newest first, so the first time seen is the last commit and
+            // the final time seen is the first
+            times.last.entry(file.to_string()).or_insert(timestamp);
+            times.first.insert(file.to_string(), timestamp);
+        }
+    }
+    times
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    /// Run git with a fixed identity and dates so tests do not depend on user config
+    fn git_at(dir: &Path, args: &[&str], date: &str) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=tester@example.com",
+            ])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_parse_commit() {
+        let line = "abcdef0123456789\x1fAlice\x1f1700000000\x1fAdd: notes";
+        let commit = parse_commit(line).unwrap();
+        assert_eq!(commit.author, "Alice");
+        assert_eq!(commit.timestamp, 1_700_000_000);
+        assert_eq!(commit.subject, "Add: notes");
+        assert_eq!(commit.short_hash(), "abcdef01");
+    }
+
+    #[test]
+    fn test_parse_commit_times() {
+        let output = "\x1e200\0\ndocs/a.md\0\x1e100\0\ndocs/a.md\0docs/caf\u{e9}.md\0";
+        let times = parse_commit_times(output);
+        assert_eq!(times.last.get("docs/a.md"), Some(&200));
+        assert_eq!(times.last.get("docs/café.md"), Some(&100));
+        assert_eq!(times.first.get("docs/a.md"), Some(&100));
+    }
+
+    #[test]
+    fn test_history_in_repository() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_git_history");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs")).unwrap();
+        assert!(!is_repository(&temp_dir));
+
+        git_at(&temp_dir, &["init", "-q"], "2026-01-01T00:00:00Z");
+        fs::write(temp_dir.join("docs/a.md"), "# A\n").unwrap();
+        fs::write(temp_dir.join("docs/café.md"), "# Café\n").unwrap();
+        git_at(&temp_dir, &["add", "."], "2026-01-01T00:00:00Z");
+        git_at(
+            &temp_dir,
+            &["commit", "-qm", "Add a"],
+            "2026-01-01T00:00:00Z",
+        );
+        fs::write(temp_dir.join("docs/a.md"), "# A\n\nMore\n").unwrap();
+        git_at(
+            &temp_dir,
+            &["commit", "-qam", "Expand a"],
+            "2026-02-01T00:00:00Z",
+        );
+
+        assert!(is_repository(&temp_dir));
+        let history = file_history(&temp_dir, Path::new("docs/a.md")).unwrap();
+        let subjects: Vec<&str> = history.iter().map(|c| c.subject.as_str()).collect();
+        assert_eq!(subjects, vec!["Expand a", "Add a"]);
+        assert_eq!(authors(&history), vec!["Tester"]);
+
+        let times = commit_times(&temp_dir).unwrap();
+        assert_eq!(times.last.get("docs/a.md"), Some(&history[0].timestamp));
+        assert_eq!(times.first.get("docs/a.md"), Some(&history[1].timestamp));
+        assert_eq!(times.first.get("docs/café.md"), Some(&history[1].timestamp));
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
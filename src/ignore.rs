@@ -0,0 +1,337 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::WalkConfig;
+
+/// Ignore file read in every directory of the library
+pub const MDLIBSIGNORE: &str = ".mdlibsignore";
+
+/// Git's ignore file, read when `gitignore` is enabled in the `[walk]` config
+pub const GITIGNORE: &str = ".gitignore";
+
+/// A single gitignore-style pattern
+#[derive(Debug, Clone, PartialEq)]
+struct Pattern {
+    glob: Vec<char>,
+    /// `!pattern`: re-include paths an earlier pattern excluded
+    negated: bool,
+    /// `pattern/`: only match directories
+    dir_only: bool,
+    /// Patterns containing a `/` match relative to their base directory; others match
+    /// a file or directory name at any depth
+    anchored: bool,
+}
+
+impl Pattern {
+    /// Parse a line of an ignore file, returning None for blank lines and comments
+    fn parse(line: &str) -> Option<Self> {
+        let mut line = line.trim_end_matches('\r');
+        // Trailing spaces are ignored unless escaped with a backslash
+        while line.ends_with(' ') && !line.ends_with("\\ ") {
+            line = &line[..line.len() - 1];
+        }
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        // A leading `!` negates the pattern; `\!` and `\#` escape a literal first character
+        let negated = line.starts_with('!');
+        if negated || line.starts_with("\\!") || line.starts_with("\\#") {
+            line = &line[1..];
+        }
+        let dir_only = line.ends_with('/');
+        let line = line.trim_end_matches('/');
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        if line.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            glob: line.chars().collect(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// Whether the pattern matches a path relative to its base directory
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let subject = if self.anchored {
+            relative
+        } else {
+            relative.rsplit('/').next().unwrap_or(relative)
+        };
+        let subject: Vec<char> = subject.chars().collect();
+        glob_match(&self.glob, &subject)
+    }
+}
+
+/// Match a gitignore glob against a `/`-separated path: `*` and `?` stay within one
+/// path segment, `**` spans segments and `[a-z]`/`[!a-z]` match character classes
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        // Leading or inner `**/` matches zero or more directories
+        ['*', '*', '/', rest @ ..] => {
+            glob_match(rest, text)
+                || text
+                    .iter()
+                    .enumerate()
+                    .any(|(i, &c)| c == '/' && glob_match(rest, &text[i + 1..]))
+        }
+        ['*', '*'] => true,
+        // Trailing `/**` matches everything inside a directory
+        ['/', '*', '*'] => text.len() > 1 && text[0] == '/',
+        ['*', ..] => {
+            let rest = {
+                let stars = pattern.iter().take_while(|&&c| c == '*').count();
+                &pattern[stars..]
+            };
+            for i in 0..=text.len() {
+                if glob_match(rest, &text[i..]) {
+                    return true;
+                }
+                if i < text.len() && text[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        ['?', rest @ ..] => !text.is_empty() && text[0] != '/' && glob_match(rest, &text[1..]),
+        ['[', class @ ..] => match match_class(class, text.first().copied()) {
+            Some((matched, len)) => matched && glob_match(&class[len..], &text[1..]),
+            None => !text.is_empty() && text[0] == '[' && glob_match(class, &text[1..]),
+        },
+        ['\\', c, rest @ ..] => !text.is_empty() && text[0] == *c && glob_match(rest, &text[1..]),
+        [c, rest @ ..] => !text.is_empty() && text[0] == *c && glob_match(rest, &text[1..]),
+    }
+}
+
+/// Match a character against a `[...]` class whose `[` has been consumed, returning
+/// whether it matched and the class length including the `]`, or None if the class is
+/// not closed
+fn match_class(class: &[char], c: Option<char>) -> Option<(bool, usize)> {
+    let negated = matches!(class.first(), Some('!' | '^'));
+    let start = usize::from(negated);
+    // A `]` right after the opening bracket is a literal member
+    let close = class
+        .iter()
+        .skip(start + 1)
+        .position(|&c| c == ']')
+        .map(|pos| pos + start + 1)?;
+    let members = &class[start..close];
+    let Some(c) = c.filter(|&c| c != '/') else {
+        return Some((false, close + 1));
+    };
+
+    let mut found = false;
+    let mut i = 0;
+    while i < members.len() {
+        if i + 2 < members.len() && members[i + 1] == '-' {
+            found |= (members[i]..=members[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            found |= members[i] == c;
+            i += 1;
+        }
+    }
+    Some((found != negated, close + 1))
+}
+
+/// Ignore rules in effect for the entries of a directory: the patterns of its own
+/// ignore files, chained to those of its parents
+#[derive(Debug)]
+pub struct Rules {
+    parent: Option<Arc<Rules>>,
+    /// Directory the patterns are relative to
+    base: PathBuf,
+    patterns: Vec<Pattern>,
+    gitignore: bool,
+}
+
+impl Rules {
+    /// Rules for the library root: the configured `exclude` globs, then the root's
+    /// `.gitignore` and `.mdlibsignore`
+    pub fn root(lib_root: &Path, config: &WalkConfig) -> Arc<Self> {
+        let mut patterns: Vec<Pattern> = config
+            .exclude
+            .iter()
+            .filter_map(|glob| Pattern::parse(glob))
+            .collect();
+        patterns.extend(read_ignore_files(lib_root, config.gitignore));
+        Arc::new(Self {
+            parent: None,
+            base: lib_root.to_path_buf(),
+            patterns,
+            gitignore: config.gitignore,
+        })
+    }
+
+    /// Rules for the entries of `dir`, a subdirectory of the directory these rules
+    /// apply to
+    pub fn enter(self: &Arc<Self>, dir: &Path) -> Arc<Self> {
+        let patterns = read_ignore_files(dir, self.gitignore);
+        if patterns.is_empty() {
+            return Arc::clone(self);
+        }
+        Arc::new(Self {
+            parent: Some(Arc::clone(self)),
+            base: dir.to_path_buf(),
+            patterns,
+            gitignore: self.gitignore,
+        })
+    }
+
+    /// Rules for the entries of a directory below the one these rules apply to,
+    /// entering each directory in between, or None if one of them is ignored
+    pub fn enter_path(self: &Arc<Self>, dir: &Path) -> Option<Arc<Self>> {
+        let mut rules = Arc::clone(self);
+        let Ok(relative) = dir.strip_prefix(&self.base) else {
+            return Some(rules);
+        };
+        let mut current = self.base.clone();
+        for component in relative.components() {
+            current.push(component);
+            if rules.is_ignored(&current, true) {
+                return None;
+            }
+            rules = rules.enter(&current);
+        }
+        Some(rules)
+    }
+
+    /// Whether a path inside the directory these rules apply to is ignored. Deeper
+    /// ignore files take precedence, and within a file the last matching pattern wins.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut rules = Some(self);
+        while let Some(current) = rules {
+            if let Ok(relative) = path.strip_prefix(&current.base) {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                if let Some(pattern) = current
+                    .patterns
+                    .iter()
+                    .rev()
+                    .find(|pattern| pattern.matches(&relative, is_dir))
+                {
+                    return !pattern.negated;
+                }
+            }
+            rules = current.parent.as_deref();
+        }
+        false
+    }
+}
+
+/// Patterns of the ignore files in a directory, `.mdlibsignore` after `.gitignore` so
+/// it takes precedence
+fn read_ignore_files(dir: &Path, gitignore: bool) -> Vec<Pattern> {
+    let names = if gitignore {
+        &[GITIGNORE, MDLIBSIGNORE][..]
+    } else {
+        &[MDLIBSIGNORE][..]
+    };
+    names
+        .iter()
+        .filter_map(|name| fs::read_to_string(dir.join(name)).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(Pattern::parse)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn matches(pattern: &str, path: &str, is_dir: bool) -> bool {
+        Pattern::parse(pattern).unwrap().matches(path, is_dir)
+    }
+
+    #[test]
+    fn test_pattern_parse() {
+        assert_eq!(Pattern::parse("# comment"), None);
+        assert_eq!(Pattern::parse("   "), None);
+        let pattern = Pattern::parse("!/build/").unwrap();
+        assert!(pattern.negated && pattern.dir_only && pattern.anchored);
+        assert_eq!(pattern.glob, vec!['b', 'u', 'i', 'l', 'd']);
+        assert_eq!(Pattern::parse("\\#notes").unwrap().glob[0], '#');
+    }
+
+    #[test]
+    fn test_unanchored_patterns_match_names_at_any_depth() {
+        assert!(matches("node_modules", "node_modules", true));
+        assert!(matches("node_modules", "a/b/node_modules", true));
+        assert!(matches("*.draft.md", "notes/idea.draft.md", false));
+        assert!(!matches("*.draft.md", "notes/idea.md", false));
+        assert!(matches("build/", "src/build", true));
+        assert!(!matches("build/", "src/build", false));
+    }
+
+    #[test]
+    fn test_anchored_patterns() {
+        assert!(matches("/drafts", "drafts", true));
+        assert!(!matches("/drafts", "docs/drafts", true));
+        assert!(matches("docs/*.md", "docs/a.md", false));
+        assert!(!matches("docs/*.md", "docs/sub/a.md", false));
+        assert!(matches("**/tmp", "a/b/tmp", true));
+        assert!(matches("**/tmp", "tmp", true));
+        assert!(matches("docs/**/old", "docs/old", true));
+        assert!(matches("docs/**/old", "docs/a/b/old", true));
+        assert!(matches("archive/**", "archive/2020/a.md", false));
+        assert!(!matches("archive/**", "archive", true));
+    }
+
+    #[test]
+    fn test_glob_classes() {
+        assert!(matches("draft-[0-9].md", "draft-3.md", false));
+        assert!(!matches("draft-[0-9].md", "draft-x.md", false));
+        assert!(matches("draft-[!0-9].md", "draft-x.md", false));
+        assert!(matches("file?.md", "file1.md", false));
+        assert!(matches("a[b", "a[b", false));
+    }
+
+    #[test]
+    fn test_rules_precedence() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_ignore_rules");
+        let _ = fs::remove_dir_all(&temp_dir);
+        let docs = temp_dir.join("docs");
+        fs::create_dir_all(docs.join("drafts")).unwrap();
+        fs::write(temp_dir.join(GITIGNORE), "*.tmp.md\n").unwrap();
+        fs::write(temp_dir.join(MDLIBSIGNORE), "drafts/\n").unwrap();
+        fs::write(docs.join(MDLIBSIGNORE), "!keep.tmp.md\n").unwrap();
+
+        let config = WalkConfig {
+            exclude: vec![String::from("docs/private.md")],
+            ..WalkConfig::default()
+        };
+        let root = Rules::root(&temp_dir, &config);
+        let docs_rules = root.enter_path(&docs).unwrap();
+        assert!(docs_rules.is_ignored(&docs.join("private.md"), false));
+        assert!(docs_rules.is_ignored(&docs.join("scratch.tmp.md"), false));
+        assert!(!docs_rules.is_ignored(&docs.join("keep.tmp.md"), false));
+        assert!(docs_rules.is_ignored(&docs.join("drafts"), true));
+        assert!(root.enter_path(&docs.join("drafts")).is_none());
+
+        // .gitignore is only read when enabled
+        let without_git = Rules::root(
+            &temp_dir,
+            &WalkConfig {
+                gitignore: false,
+                ..WalkConfig::default()
+            },
+        );
+        assert!(!without_git.is_ignored(&temp_dir.join("scratch.tmp.md"), false));
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::commands::list::{DocumentEntry, DocumentType};
+use crate::markdown::{self, Block, Inline};
+use crate::parallel;
+
+/// A link, image or URL in a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    /// Line number, starting at 1
+    pub line: usize,
+    /// The destination as written
+    pub target: String,
+    /// Whether the link is an image (`![alt](src)`)
+    pub image: bool,
+}
+
+/// Where a link points
+#[derive(Debug, Clone, PartialEq)]
+pub enum Destination {
+    /// A URL with a scheme, such as `https:` or `mailto:`
+    External,
+    /// A heading in the same document (`#section`)
+    Anchor,
+    /// A file by path relative to the library root, without any `#fragment`
+    Local(String),
+}
+
+/// Links, images and URLs in the prose and headings of a document: inline links,
+/// reference definitions (`[label]: target`), autolinks and bare URLs. Frontmatter,
+/// code blocks, code spans and HTML comments are skipped.
+pub fn extract(content: &str) -> Vec<Link> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut links = Vec::new();
+    let mut in_comment = false;
+    for (index, (line, block)) in lines.iter().zip(markdown::blocks(&lines)).enumerate() {
+        if !matches!(block, Block::Text | Block::Heading) {
+            continue;
+        }
+        if !in_comment {
+            if let Some(target) = reference_definition(line) {
+                links.push(Link {
+                    line: index + 1,
+                    target: target.to_string(),
+                    image: false,
+                });
+                continue;
+            }
+        }
+
+        let spans = markdown::inline_spans(line, &mut in_comment);
+        for (i, span) in spans.iter().enumerate() {
+            let image = match span.kind {
+                Inline::LinkTarget => {
+                    is_image(line, span.range.start, i.checked_sub(1).map(|i| &spans[i]))
+                }
+                Inline::Url => false,
+                _ => continue,
+            };
+            links.push(Link {
+                line: index + 1,
+                target: line[span.range.clone()].to_string(),
+                image,
+            });
+        }
+    }
+    links
+}
+
+/// The target of a reference definition line (`[label]: target "title"`)
+fn reference_definition(line: &str) -> Option<&str> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    let rest = line[indent..].strip_prefix('[')?;
+    if indent > 3 || rest.starts_with('^') {
+        return None;
+    }
+    let close = rest.find("]:")?;
+    if close == 0 {
+        return None;
+    }
+    let target = rest[close + 2..].split_whitespace().next()?;
+    let target = target
+        .strip_prefix('<')
+        .and_then(|t| t.strip_suffix('>'))
+        .unwrap_or(target);
+    (!target.is_empty()).then_some(target)
+}
+
+/// Whether the link whose target starts at `target_start` is an image: its `[` is
+/// preceded by `!`. `text` is the span before the target, the link text if it has any.
+fn is_image(line: &str, target_start: usize, text: Option<&markdown::InlineSpan>) -> bool {
+    let before = line[..target_start].trim_end_matches(|c: char| c == '<' || c.is_whitespace());
+    let before = before.strip_suffix('(').unwrap_or(before);
+    if before.ends_with("![]") {
+        return true;
+    }
+    match text {
+        Some(span) if span.kind == Inline::LinkText && span.range.end == before.len() - 1 => {
+            line[..span.range.start].ends_with("![")
+        }
+        _ => false,
+    }
+}
+
+/// Resolve a link target written in the document at `from` (relative to the library
+/// root). Paths starting with `/` are relative to the library root.
+pub fn resolve(from: &str, target: &str) -> Destination {
+    if target.starts_with("//") || has_scheme(target) {
+        return Destination::External;
+    }
+    let path = target.split(['#', '?']).next().unwrap_or("");
+    if path.is_empty() {
+        return Destination::Anchor;
+    }
+    let path = percent_decode(path);
+
+    let mut components: Vec<&str> = Vec::new();
+    if let Some(absolute) = path.strip_prefix('/') {
+        components.extend(absolute.split('/'));
+    } else {
+        components.extend(from.split('/'));
+        components.pop();
+        components.extend(path.split('/'));
+    }
+
+    let mut resolved: Vec<&str> = Vec::new();
+    for component in components {
+        match component {
+            "" | "." => {}
+            ".." if resolved.last().is_some_and(|last| *last != "..") => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    Destination::Local(resolved.join("/"))
+}
+
+/// Whether a target starts with a URL scheme such as `https:` (single letters are
+/// left out so Windows drive letters are not mistaken for schemes)
+fn has_scheme(target: &str) -> bool {
+    target.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// Decode `%XX` escapes, such as `%20` for a space in a file name
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| path.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| path.to_string())
+}
+
+/// Links between the documents of a library, keyed by path relative to the library root
+#[derive(Debug, Default)]
+pub struct LinkGraph {
+    /// Other documents each document links to
+    pub outbound: BTreeMap<String, BTreeSet<String>>,
+    /// Other documents linking to each document
+    pub inbound: BTreeMap<String, BTreeSet<String>>,
+    /// Links to local files that do not exist, per document
+    pub broken: BTreeMap<String, Vec<Link>>,
+}
+
+impl LinkGraph {
+    /// Read every document and collect the links between them
+    pub fn build(lib_root: &Path, documents: &[DocumentEntry], threads: usize) -> Self {
+        let links = parallel::map(documents, parallel::thread_count(threads), |doc| {
+            fs::read_to_string(lib_root.join(&doc.path))
+                .map(|content| extract(&content))
+                .unwrap_or_default()
+        });
+        Self::from_links(lib_root, documents, &links)
+    }
+
+    /// Build the graph from the links already extracted from each document
+    pub fn from_links(lib_root: &Path, documents: &[DocumentEntry], links: &[Vec<Link>]) -> Self {
+        let paths: HashSet<&str> = documents.iter().map(|doc| doc.path.as_str()).collect();
+        let mut graph = Self::default();
+        for (doc, links) in documents.iter().zip(links) {
+            for link in links {
+                let Destination::Local(target) = resolve(&doc.path, &link.target) else {
+                    continue;
+                };
+                if paths.contains(target.as_str()) {
+                    if target != doc.path {
+                        graph
+                            .outbound
+                            .entry(doc.path.clone())
+                            .or_default()
+                            .insert(target.clone());
+                        graph
+                            .inbound
+                            .entry(target)
+                            .or_default()
+                            .insert(doc.path.clone());
+                    }
+                } else if !lib_root.join(&target).exists() {
+                    graph
+                        .broken
+                        .entry(doc.path.clone())
+                        .or_default()
+                        .push(link.clone());
+                }
+            }
+        }
+        graph
+    }
+
+    /// Documents no other document links to, leaving out templates and entry points
+    pub fn orphans<'a>(
+        &self,
+        documents: &'a [DocumentEntry],
+        entry_points: &[String],
+    ) -> Vec<&'a DocumentEntry> {
+        documents
+            .iter()
+            .filter(|doc| doc.doc_type == DocumentType::Document)
+            .filter(|doc| !entry_points.contains(&doc.path))
+            .filter(|doc| !self.inbound.contains_key(&doc.path))
+            .collect()
+    }
+
+    /// Documents that link to no other document, leaving out templates
+    pub fn dead_ends<'a>(&self, documents: &'a [DocumentEntry]) -> Vec<&'a DocumentEntry> {
+        documents
+            .iter()
+            .filter(|doc| doc.doc_type == DocumentType::Document)
+            .filter(|doc| !self.outbound.contains_key(&doc.path))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn targets(content: &str) -> Vec<(usize, String, bool)> {
+        extract(content)
+            .into_iter()
+            .map(|link| (link.line, link.target, link.image))
+            .collect()
+    }
+
+    #[test]
+    fn test_extract() {
+        let content = "---\nlink: [x](front.md)\n---\n# [Guide](guide.md)\n\nSee [a](a.md \"A\"), ![logo](img/logo.png) and ![](blank.png).\n`[no](code.md)` <!-- [no](comment.md) -->\n<https://example.com> or https://rust-lang.org.\n\n```\n[no](fenced.md)\n```\n[ref]: <notes/ref.md> \"Title\"\n";
+        assert_eq!(
+            targets(content),
+            vec![
+                (4, String::from("guide.md"), false),
+                (6, String::from("a.md"), false),
+                (6, String::from("img/logo.png"), true),
+                (6, String::from("blank.png"), true),
+                (8, String::from("https://example.com"), false),
+                (8, String::from("https://rust-lang.org"), false),
+                (13, String::from("notes/ref.md"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve("docs/a.md", "https://x.org"), Destination::External);
+        assert_eq!(
+            resolve("docs/a.md", "mailto:me@x.org"),
+            Destination::External
+        );
+        assert_eq!(resolve("docs/a.md", "#intro"), Destination::Anchor);
+        assert_eq!(
+            resolve("docs/a.md", "b.md#intro"),
+            Destination::Local(String::from("docs/b.md"))
+        );
+        assert_eq!(
+            resolve("docs/notes/a.md", "../My%20Doc.md"),
+            Destination::Local(String::from("docs/My Doc.md"))
+        );
+        assert_eq!(
+            resolve("docs/a.md", "/templates/./note.md"),
+            Destination::Local(String::from("templates/note.md"))
+        );
+        assert_eq!(
+            resolve("a.md", "../outside.md"),
+            Destination::Local(String::from("../outside.md"))
+        );
+        assert_eq!(
+            resolve("a.md", "C:/notes/x.md"),
+            Destination::Local(String::from("C:/notes/x.md"))
+        );
+    }
+
+    fn entry(path: &str, doc_type: DocumentType) -> DocumentEntry {
+        DocumentEntry {
+            path: path.to_string(),
+            title: path.to_string(),
+            doc_type,
+        }
+    }
+
+    #[test]
+    fn test_link_graph() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_links_graph");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs/img")).unwrap();
+        fs::write(temp_dir.join("docs/img/logo.png"), "").unwrap();
+        fs::write(
+            temp_dir.join("docs/README.md"),
+            "# Home\n\n[A](a.md) [A again](a.md#top) [gone](gone.md)\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.join("docs/a.md"),
+            "# A\n\n[home](README.md) [self](#top) ![logo](img/logo.png)\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.join("docs/b.md"), "# B\n").unwrap();
+
+        let documents = vec![
+            entry("docs/README.md", DocumentType::Document),
+            entry("docs/a.md", DocumentType::Document),
+            entry("docs/b.md", DocumentType::Document),
+            entry("templates/t.md", DocumentType::Template),
+        ];
+        let graph = LinkGraph::build(&temp_dir, &documents, 1);
+        assert_eq!(graph.outbound["docs/README.md"].len(), 1);
+        assert!(graph.inbound["docs/README.md"].contains("docs/a.md"));
+        assert_eq!(graph.broken["docs/README.md"][0].target, "gone.md");
+        assert!(!graph.broken.contains_key("docs/a.md"));
+
+        let orphans: Vec<&str> = graph
+            .orphans(&documents, &[String::from("docs/README.md")])
+            .iter()
+            .map(|doc| doc.path.as_str())
+            .collect();
+        assert_eq!(orphans, vec!["docs/b.md"]);
+        let dead_ends: Vec<&str> = graph
+            .dead_ends(&documents)
+            .iter()
+            .map(|doc| doc.path.as_str())
+            .collect();
+        assert_eq!(dead_ends, vec!["docs/b.md"]);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -0,0 +1,580 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::config::LintConfig;
+use crate::frontmatter;
+use crate::markdown::{code_lines, headings};
+
+/// Prefixes that start a URL
+const URL_SCHEMES: [&str; 2] = ["https://", "http://"];
+
+/// Characters that, directly before a URL, show it is already part of a link or tag
+const LINKED_URL_PREFIXES: [char; 6] = ['(', '<', '[', '"', '\'', '='];
+
+/// A problem found in a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// One-based line number
+    pub line: usize,
+    /// One-based column (in characters)
+    pub column: usize,
+    /// Identifier of the rule that produced the diagnostic
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {} {}",
+            self.line, self.column, self.rule, self.message
+        )
+    }
+}
+
+/// Check a document against the enabled lint rules, returning diagnostics in line order
+pub fn lint(content: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = content.lines().collect();
+    let body_start = frontmatter::body_start(&lines);
+    let in_code = code_lines(&lines);
+    let mut diagnostics = Vec::new();
+
+    check_headings(content, body_start, config, &mut diagnostics);
+    if config.heading_spacing {
+        check_heading_blank_lines(content, &lines, body_start, &mut diagnostics);
+    }
+    if !config.required_frontmatter.is_empty() {
+        check_required_frontmatter(content, config, &mut diagnostics);
+    }
+    if config.final_newline && !content.is_empty() && !content.ends_with('\n') {
+        let last = lines.last().copied().unwrap_or_default();
+        diagnostics.push(Diagnostic {
+            line: lines.len(),
+            column: last.chars().count() + 1,
+            rule: "final-newline",
+            message: String::from("Document does not end with a newline"),
+        });
+    }
+
+    let mut list_marker = configured_marker(config);
+    for (index, line) in lines.iter().enumerate().skip(body_start) {
+        let line_number = index + 1;
+        if config.trailing_whitespace {
+            check_trailing_whitespace(line, line_number, in_code[index], &mut diagnostics);
+        }
+        if in_code[index] {
+            continue;
+        }
+        if config.heading_spacing {
+            check_heading_space(line, line_number, &mut diagnostics);
+        }
+        if config.bare_url {
+            check_bare_urls(line, line_number, &mut diagnostics);
+        }
+        if config.list_marker != "off" {
+            check_list_marker(line, line_number, &mut list_marker, &mut diagnostics);
+        }
+        if config.line_length > 0 {
+            check_line_length(line, line_number, config.line_length, &mut diagnostics);
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// Rewrite a document to fix the problems of the enabled rules that can be fixed
+/// mechanically: trailing whitespace, heading spacing, list markers and the final newline.
+///
+/// Frontmatter, line endings and whether the document ends with a newline (unless the
+/// final-newline rule requires one) are preserved.
+pub fn fix(content: &str, config: &LintConfig) -> String {
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let original: Vec<&str> = content.lines().collect();
+    let body_start = frontmatter::body_start(&original);
+    let in_code = code_lines(&original);
+    let mut expected_marker = configured_marker(config);
+
+    let mut lines: Vec<String> = Vec::with_capacity(original.len());
+    for (index, line) in original.iter().enumerate() {
+        let mut line = line.to_string();
+        if index >= body_start {
+            if config.trailing_whitespace {
+                if let Some(start) = trailing_whitespace_start(&line, in_code[index]) {
+                    line.truncate(start);
+                }
+            }
+            if !in_code[index] && config.list_marker != "off" {
+                if let Some((indent, marker)) = list_marker(&line) {
+                    match expected_marker {
+                        None => expected_marker = Some(marker),
+                        Some(expected) if expected != marker => {
+                            line.replace_range(indent..indent + 1, &expected.to_string())
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+            if !in_code[index] && config.heading_spacing {
+                if let Some(fixed) = normalize_heading_space(&line) {
+                    line = fixed;
+                }
+            }
+        }
+        lines.push(line);
+    }
+    if config.heading_spacing {
+        lines = add_blank_lines_around_headings(lines, body_start);
+    }
+
+    let mut result = lines.join(newline);
+    let needs_final_newline = config.final_newline && !content.is_empty();
+    if content.ends_with('\n') || needs_final_newline {
+        result.push_str(newline);
+    }
+    result
+}
+
+/// The list marker required by the configuration, if it names one
+fn configured_marker(config: &LintConfig) -> Option<char> {
+    match config.list_marker.as_str() {
+        "-" | "*" | "+" => config.list_marker.chars().next(),
+        _ => None,
+    }
+}
+
+/// Insert blank lines before and after headings where they are missing
+fn add_blank_lines_around_headings(lines: Vec<String>, body_start: usize) -> Vec<String> {
+    let heading_lines: HashSet<usize> = headings(&lines.join("\n"))
+        .into_iter()
+        .map(|heading| heading.line)
+        .collect();
+
+    let is_blank = |line: &String| line.trim().is_empty();
+    let mut result: Vec<String> = Vec::with_capacity(lines.len());
+    for (index, line) in lines.iter().enumerate() {
+        let is_heading = heading_lines.contains(&index);
+        // Checking the output keeps consecutive headings to a single blank line between them
+        let previous_blank = result.last().map(is_blank).unwrap_or(true);
+        if is_heading && index > body_start && !previous_blank {
+            result.push(String::new());
+        }
+        result.push(line.clone());
+        let next_blank = lines.get(index + 1).map(is_blank).unwrap_or(true);
+        if is_heading && !next_blank {
+            result.push(String::new());
+        }
+    }
+    result
+}
+
+/// missing-h1, multiple-h1 and heading-increment
+fn check_headings(
+    content: &str,
+    body_start: usize,
+    config: &LintConfig,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let headings = headings(content);
+
+    let mut h1_count = 0;
+    let mut previous_level: Option<usize> = None;
+    for heading in &headings {
+        let line = heading.line + 1;
+        if heading.level == 1 {
+            h1_count += 1;
+            if h1_count > 1 && config.multiple_h1 {
+                diagnostics.push(Diagnostic {
+                    line,
+                    column: 1,
+                    rule: "multiple-h1",
+                    message: String::from("Document has more than one level 1 heading"),
+                });
+            }
+        }
+        if let Some(previous) = previous_level {
+            if heading.level > previous + 1 && config.heading_increment {
+                diagnostics.push(Diagnostic {
+                    line,
+                    column: 1,
+                    rule: "heading-increment",
+                    message: format!(
+                        "Heading level skipped: h{} follows h{}",
+                        heading.level, previous
+                    ),
+                });
+            }
+        }
+        previous_level = Some(heading.level);
+    }
+
+    if h1_count == 0 && config.missing_h1 {
+        diagnostics.push(Diagnostic {
+            line: body_start + 1,
+            column: 1,
+            rule: "missing-h1",
+            message: String::from("Document has no level 1 heading"),
+        });
+    }
+}
+
+/// required-frontmatter
+fn check_required_frontmatter(
+    content: &str,
+    config: &LintConfig,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for key in &config.required_frontmatter {
+        let present = frontmatter::get(content, key).is_some()
+            || frontmatter::get_list(content, key).is_some();
+        if !present {
+            diagnostics.push(Diagnostic {
+                line: 1,
+                column: 1,
+                rule: "required-frontmatter",
+                message: format!("Missing frontmatter key: {}", key),
+            });
+        }
+    }
+}
+
+/// Byte offset at which a line's trailing whitespace starts, if it has any that should
+/// be removed; exactly two spaces after prose are a hard line break and allowed
+fn trailing_whitespace_start(line: &str, in_code: bool) -> Option<usize> {
+    let trimmed = line.trim_end();
+    if trimmed.len() == line.len() {
+        return None;
+    }
+    let hard_break = !in_code && !trimmed.is_empty() && line[trimmed.len()..] == *"  ";
+    (!hard_break).then_some(trimmed.len())
+}
+
+/// trailing-whitespace
+fn check_trailing_whitespace(
+    line: &str,
+    line_number: usize,
+    in_code: bool,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(start) = trailing_whitespace_start(line, in_code) {
+        diagnostics.push(Diagnostic {
+            line: line_number,
+            column: line[..start].chars().count() + 1,
+            rule: "trailing-whitespace",
+            message: String::from("Trailing whitespace"),
+        });
+    }
+}
+
+/// A heading line rewritten with exactly one space after its `#`s, if it has some other
+/// spacing. A single `#` directly followed by text is left alone, as it is more likely a
+/// hashtag than a heading.
+fn normalize_heading_space(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    if indent.len() > 3 {
+        return None;
+    }
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    let text = rest.trim_start();
+    let spacing = &rest[..rest.len() - text.len()];
+    if text.is_empty() || spacing == " " || (spacing.is_empty() && level == 1) {
+        return None;
+    }
+    Some(format!("{}{} {}", indent, &trimmed[..level], text))
+}
+
+/// heading-spacing: exactly one space between the `#`s and the heading text
+fn check_heading_space(line: &str, line_number: usize, diagnostics: &mut Vec<Diagnostic>) {
+    if normalize_heading_space(line).is_some() {
+        let hashes_end = line.len() - line.trim_start().len()
+            + line.trim_start().chars().take_while(|&c| c == '#').count();
+        diagnostics.push(Diagnostic {
+            line: line_number,
+            column: hashes_end + 1,
+            rule: "heading-spacing",
+            message: String::from("Heading should have exactly one space after the '#'s"),
+        });
+    }
+}
+
+/// heading-spacing: headings are surrounded by blank lines
+fn check_heading_blank_lines(
+    content: &str,
+    lines: &[&str],
+    body_start: usize,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for heading in headings(content) {
+        let index = heading.line;
+        if index > body_start && !lines[index - 1].trim().is_empty() {
+            diagnostics.push(Diagnostic {
+                line: index + 1,
+                column: 1,
+                rule: "heading-spacing",
+                message: String::from("Heading should be preceded by a blank line"),
+            });
+        }
+        if index + 1 < lines.len() && !lines[index + 1].trim().is_empty() {
+            diagnostics.push(Diagnostic {
+                line: index + 1,
+                column: 1,
+                rule: "heading-spacing",
+                message: String::from("Heading should be followed by a blank line"),
+            });
+        }
+    }
+}
+
+/// bare-url: URLs in prose that are not inside a link, an autolink or a code span
+fn check_bare_urls(line: &str, line_number: usize, diagnostics: &mut Vec<Diagnostic>) {
+    let mut in_code_span = false;
+    for (idx, c) in line.char_indices() {
+        if c == '`' {
+            in_code_span = !in_code_span;
+            continue;
+        }
+        if in_code_span || !URL_SCHEMES.iter().any(|s| line[idx..].starts_with(s)) {
+            continue;
+        }
+        let linked = line[..idx]
+            .chars()
+            .next_back()
+            .map(|prev| LINKED_URL_PREFIXES.contains(&prev))
+            .unwrap_or(false);
+        if !linked {
+            diagnostics.push(Diagnostic {
+                line: line_number,
+                column: line[..idx].chars().count() + 1,
+                rule: "bare-url",
+                message: String::from("Bare URL (write it as <url> or [text](url))"),
+            });
+        }
+    }
+}
+
+/// The marker of an unordered list item, if the line is one
+fn list_marker(line: &str) -> Option<(usize, char)> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let marker = rest
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '-' | '*' | '+'))?;
+    if !rest[1..].starts_with([' ', '\t']) {
+        return None;
+    }
+    // `* * *` and `- - -` are thematic breaks, not lists
+    if rest.chars().all(|c| c == marker || c.is_whitespace()) {
+        return None;
+    }
+    Some((indent, marker))
+}
+
+/// list-marker: unordered lists use the configured marker, or the first one used
+fn check_list_marker(
+    line: &str,
+    line_number: usize,
+    expected: &mut Option<char>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some((indent, marker)) = list_marker(line) else {
+        return;
+    };
+    match *expected {
+        None => *expected = Some(marker),
+        Some(expected) if expected != marker => diagnostics.push(Diagnostic {
+            line: line_number,
+            column: line[..indent].chars().count() + 1,
+            rule: "list-marker",
+            message: format!("List marker '{}' should be '{}'", marker, expected),
+        }),
+        Some(_) => {}
+    }
+}
+
+/// line-length; lines without spaces (such as long URLs) cannot be wrapped and are skipped
+fn check_line_length(
+    line: &str,
+    line_number: usize,
+    max: usize,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let length = line.chars().count();
+    if length > max && line.trim().contains(char::is_whitespace) {
+        diagnostics.push(Diagnostic {
+            line: line_number,
+            column: max + 1,
+            rule: "line-length",
+            message: format!("Line is {} characters long (maximum {})", length, max),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(content: &str, config: &LintConfig) -> Vec<(usize, usize, &'static str)> {
+        lint(content, config)
+            .into_iter()
+            .map(|d| (d.line, d.column, d.rule))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_document() {
+        let content = "# Title\n\n## Section\n\n- one\n- two\n\nSee <https://example.com>.\n";
+        assert_eq!(lint(content, &LintConfig::default()), Vec::new());
+    }
+
+    #[test]
+    fn test_heading_rules() {
+        let config = LintConfig::default();
+        assert_eq!(rules("Text\n", &config), vec![(1, 1, "missing-h1")]);
+        assert_eq!(
+            rules("# A\n\n#### Deep\n\n# B\n", &config),
+            vec![(3, 1, "heading-increment"), (5, 1, "multiple-h1")]
+        );
+        // Headings in code blocks and frontmatter comments do not count
+        assert_eq!(
+            rules("---\n# c\n---\n# A\n\n```\n# B\n```\n", &config),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn test_trailing_whitespace() {
+        let config = LintConfig::default();
+        assert_eq!(
+            rules("# A\n\ntext \nbreak  \n\t\n", &config),
+            vec![(3, 5, "trailing-whitespace"), (5, 1, "trailing-whitespace")]
+        );
+    }
+
+    #[test]
+    fn test_bare_url() {
+        let config = LintConfig::default();
+        let content =
+            "# A\n\nSee https://a.com, [x](https://b.com), <http://c.com> and `https://d.com`.\n";
+        assert_eq!(rules(content, &config), vec![(3, 5, "bare-url")]);
+        // URLs in code blocks are fine
+        assert_eq!(
+            rules("# A\n\n```\nhttps://a.com\n```\n", &config),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn test_list_marker() {
+        let mut config = LintConfig::default();
+        let content = "# A\n\n- one\n* two\n  + three\n\n* * *\n";
+        assert_eq!(
+            rules(content, &config),
+            vec![(4, 1, "list-marker"), (5, 3, "list-marker")]
+        );
+
+        config.list_marker = String::from("*");
+        assert_eq!(
+            rules(content, &config),
+            vec![(3, 1, "list-marker"), (5, 3, "list-marker")]
+        );
+
+        config.list_marker = String::from("off");
+        assert_eq!(rules(content, &config), Vec::new());
+    }
+
+    #[test]
+    fn test_line_length() {
+        let config = LintConfig {
+            line_length: 20,
+            ..LintConfig::default()
+        };
+        let long_url = format!("<https://example.com/{}>", "a".repeat(30));
+        let content = format!("# A\n\nThis line is definitely too long.\n{}\n", long_url);
+        assert_eq!(rules(&content, &config), vec![(3, 21, "line-length")]);
+    }
+
+    #[test]
+    fn test_required_frontmatter() {
+        let config = LintConfig {
+            required_frontmatter: vec![String::from("status"), String::from("tags")],
+            ..LintConfig::default()
+        };
+        let content = "---\ntags:\n  - a\n---\n# A\n";
+        let diagnostics = lint(content, &config);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, "required-frontmatter");
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "1:1: required-frontmatter Missing frontmatter key: status"
+        );
+    }
+
+    #[test]
+    fn test_heading_spacing() {
+        let config = LintConfig::default();
+        assert_eq!(
+            rules("# A\n\n##B\n\n#tag\n\n##   C\ntext\n", &config),
+            vec![
+                (3, 3, "heading-spacing"),
+                (7, 1, "heading-spacing"),
+                (7, 3, "heading-spacing")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_final_newline() {
+        let config = LintConfig::default();
+        assert_eq!(rules("# A", &config), vec![(1, 4, "final-newline")]);
+    }
+
+    #[test]
+    fn test_fix() {
+        let config = LintConfig::default();
+        let content =
+            "---\ntitle: x  \n---\n# Title \nText \n##Section\n- one\n* two\n\n```\n* code \n```";
+        let fixed = fix(content, &config);
+        assert_eq!(
+            fixed,
+            "---\ntitle: x  \n---\n# Title\n\nText\n\n## Section\n\n- one\n- two\n\n```\n* code\n```\n"
+        );
+        // Fixing again changes nothing, and nothing fixable is left
+        assert_eq!(fix(&fixed, &config), fixed);
+        assert_eq!(lint(&fixed, &config), Vec::new());
+    }
+
+    #[test]
+    fn test_fix_preserves_line_endings_and_hard_breaks() {
+        let config = LintConfig {
+            final_newline: false,
+            ..LintConfig::default()
+        };
+        assert_eq!(
+            fix("# A\r\n\r\nline  \r\nnext\t", &config),
+            "# A\r\n\r\nline  \r\nnext"
+        );
+        assert_eq!(fix("#tag \n", &config), "#tag\n");
+    }
+
+    #[test]
+    fn test_disabled_rules() {
+        let config = LintConfig {
+            missing_h1: false,
+            trailing_whitespace: false,
+            bare_url: false,
+            ..LintConfig::default()
+        };
+        assert_eq!(rules("text \nhttps://a.com\n", &config), Vec::new());
+    }
+}
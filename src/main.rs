@@ -2,7 +2,23 @@ use clap::{Parser, Subcommand};
 
 mod commands;
 mod config;
+mod date;
+mod diff;
+mod format;
+mod frontmatter;
+mod git;
+mod hash;
+mod ignore;
+mod json;
+mod links;
+mod lint;
+mod markdown;
+mod parallel;
+mod query;
+mod snapshot;
+mod storage;
 mod utils;
+mod walker;
 
 #[derive(Parser)]
 #[command(name = "mdlibs")]
@@ -32,14 +48,67 @@ enum Commands {
         /// Filter documents by title or path content
         #[arg(short, long)]
         filter: Option<String>,
+        /// Sort documents
+        #[arg(short, long, value_enum)]
+        sort: Option<commands::list::SortKey>,
+        /// Reverse the sort order
+        #[arg(short, long)]
+        reverse: bool,
+        /// Show at most this many documents
+        #[arg(short = 'n', long, value_name = "NUM")]
+        limit: Option<usize>,
+        /// Skip this many documents
+        #[arg(long, value_name = "NUM", default_value_t = 0)]
+        offset: usize,
+        /// Columns to show, separated by commas: type, title, path, modified, created,
+        /// size, words or any frontmatter key
+        #[arg(
+            short,
+            long,
+            value_name = "COLUMNS",
+            value_delimiter = ',',
+            value_parser = commands::list::parse_column
+        )]
+        columns: Vec<commands::list::Column>,
+        /// Show documents as a directory tree
+        #[arg(short, long, conflicts_with_all = ["limit", "offset", "columns"])]
+        tree: bool,
+        /// Directory levels to expand in the tree
+        #[arg(short, long, value_name = "NUM", requires = "tree")]
+        depth: Option<usize>,
     },
     /// Update metadata or content of a markdown document
     Update {
         /// Document path or name (with or without .md extension)
-        document: String,
+        #[arg(
+            required_unless_present = "where_query",
+            conflicts_with = "where_query"
+        )]
+        document: Option<String>,
         /// New title for the document
-        #[arg(short, long)]
+        #[arg(short, long, conflicts_with = "where_query")]
         title: Option<String>,
+        /// Set a frontmatter key (repeatable)
+        #[arg(long = "set", value_name = "KEY=VALUE", value_parser = commands::update::parse_key_value)]
+        set: Vec<(String, String)>,
+        /// Remove a frontmatter key (repeatable)
+        #[arg(long, value_name = "KEY")]
+        unset: Vec<String>,
+        /// Add a tag to the `tags` list (repeatable)
+        #[arg(long = "add-tag", value_name = "TAG")]
+        add_tags: Vec<String>,
+        /// Remove a tag from the `tags` list (repeatable)
+        #[arg(long = "remove-tag", value_name = "TAG")]
+        remove_tags: Vec<String>,
+        /// Set the `status` frontmatter key
+        #[arg(long)]
+        status: Option<String>,
+        /// Edit every document matching a query (e.g. 'tag:rust status:draft')
+        #[arg(long = "where", value_name = "QUERY")]
+        where_query: Option<String>,
+        /// Apply bulk edits instead of only previewing them
+        #[arg(long, requires = "where_query")]
+        yes: bool,
     },
     /// Search through markdown documents
     Search {
@@ -48,6 +117,129 @@ enum Commands {
         /// Search only in document titles
         #[arg(short, long)]
         title_only: bool,
+        /// Lines of context to show around each match
+        #[arg(short = 'C', long, value_name = "NUM")]
+        context: Option<usize>,
+        /// Lines of context to show after each match
+        #[arg(short = 'A', long, value_name = "NUM")]
+        after_context: Option<usize>,
+        /// Lines of context to show before each match
+        #[arg(short = 'B', long, value_name = "NUM")]
+        before_context: Option<usize>,
+        /// Only match in part of each document
+        #[arg(
+            long = "in",
+            value_enum,
+            value_name = "SCOPE",
+            conflicts_with = "title_only"
+        )]
+        scope: Option<commands::search::Scope>,
+        /// Only match in fenced code blocks of this language
+        #[arg(long, value_name = "LANG", conflicts_with = "title_only")]
+        lang: Option<String>,
+        /// When to highlight matches
+        #[arg(long, value_enum, default_value_t = commands::search::ColorChoice::Auto)]
+        color: commands::search::ColorChoice,
+    },
+    /// Show details about a document: frontmatter, outline, links, tasks and more
+    Info {
+        /// Document path or name (with or without .md extension)
+        document: String,
+    },
+    /// List documents with no inbound links and documents with no outbound links
+    Orphans {
+        /// Only show one part of the report
+        #[arg(long, value_enum)]
+        only: Option<commands::orphans::ReportPart>,
+    },
+    /// Show statistics about the library
+    Stats {
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Open a document in $VISUAL or $EDITOR
+    Edit {
+        /// Document path, name or title (fuzzy matched)
+        query: String,
+    },
+    /// Insert or refresh a document's table of contents
+    Toc {
+        /// Document path or name (with or without .md extension)
+        document: String,
+        /// Deepest heading level to include
+        #[arg(short, long, default_value_t = 3, value_parser = clap::value_parser!(u8).range(2..=6))]
+        depth: u8,
+        /// Fail if the table of contents is out of date instead of updating it
+        #[arg(long)]
+        check: bool,
+    },
+    /// Generate or refresh the library index page
+    Index {
+        /// How to group documents (defaults to `group_by` in the [index] config section)
+        #[arg(short, long, value_enum)]
+        group_by: Option<commands::index::GroupBy>,
+        /// Index document to write, relative to the library root (defaults to docs/index.md)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Check documents for common markdown problems
+    Lint {
+        /// Documents to check (all documents in the library by default)
+        documents: Vec<String>,
+        /// Rewrite documents to fix problems that can be fixed automatically
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Format documents consistently
+    Fmt {
+        /// Documents to format (all documents in the library by default)
+        documents: Vec<String>,
+        /// Report unformatted documents instead of formatting them
+        #[arg(long)]
+        check: bool,
+    },
+    /// Show the git commits that touched a document
+    History {
+        /// Document path or name (with or without .md extension)
+        document: String,
+    },
+    /// List the stored snapshots of a document
+    Versions {
+        /// Document path or name (with or without .md extension)
+        document: String,
+    },
+    /// Show changes between a snapshot and the current document
+    Diff {
+        /// Document path or name (with or without .md extension)
+        document: String,
+        /// Snapshot revision (hash prefix, defaults to the latest snapshot)
+        rev: Option<String>,
+    },
+    /// Restore a document to a stored snapshot
+    Restore {
+        /// Document path or name (with or without .md extension)
+        document: String,
+        /// Snapshot revision (hash prefix)
+        rev: String,
+    },
+    /// Open or create a dated journal entry
+    Journal {
+        /// Date of the entry (YYYY-MM-DD, defaults to today in UTC)
+        #[arg(short, long, global = true)]
+        date: Option<String>,
+        #[command(subcommand)]
+        action: Option<JournalAction>,
+    },
+}
+
+#[derive(Subcommand)]
+enum JournalAction {
+    /// List journal entries
+    List {
+        /// Only show entries from the week containing the date
+        #[arg(short, long)]
+        week: bool,
     },
 }
 
@@ -56,9 +248,93 @@ fn main() {
 
     let result = match &cli.command {
         Commands::Init { path } => commands::init::run(path),
-        Commands::List { filter } => commands::list::run(filter.as_deref()),
-        Commands::Update { document, title } => commands::update::run(document, title.as_deref()),
-        Commands::Search { query, title_only } => commands::search::run(query, *title_only),
+        Commands::List {
+            filter,
+            sort,
+            reverse,
+            limit,
+            offset,
+            columns,
+            tree,
+            depth,
+        } => {
+            let options = commands::list::ListOptions {
+                filter: filter.clone(),
+                sort: *sort,
+                reverse: *reverse,
+                offset: *offset,
+                limit: *limit,
+                columns: columns.clone(),
+                tree: *tree,
+                depth: *depth,
+            };
+            commands::list::run(&options)
+        }
+        Commands::Update {
+            document,
+            title,
+            set,
+            unset,
+            add_tags,
+            remove_tags,
+            status,
+            where_query,
+            yes,
+        } => {
+            let edits = commands::update::MetadataEdits {
+                set: set.clone(),
+                unset: unset.clone(),
+                add_tags: add_tags.clone(),
+                remove_tags: remove_tags.clone(),
+                status: status.clone(),
+            };
+            match (where_query, document) {
+                (Some(query), _) => commands::update::run_bulk(query, &edits, *yes),
+                (None, Some(document)) => commands::update::run(document, title.as_deref(), &edits),
+                (None, None) => unreachable!("clap requires a document or --where"),
+            }
+        }
+        Commands::Search {
+            query,
+            title_only,
+            context,
+            after_context,
+            before_context,
+            scope,
+            lang,
+            color,
+        } => {
+            let options = commands::search::SearchOptions {
+                title_only: *title_only,
+                scope: *scope,
+                lang: lang.clone(),
+                before: before_context.or(*context).unwrap_or(0),
+                after: after_context.or(*context).unwrap_or(0),
+            };
+            commands::search::run(query, &options, *color)
+        }
+        Commands::Info { document } => commands::info::run(document),
+        Commands::Orphans { only } => commands::orphans::run(*only),
+        Commands::Stats { json } => commands::stats::run(*json),
+        Commands::Edit { query } => commands::edit::run(query),
+        Commands::Toc {
+            document,
+            depth,
+            check,
+        } => commands::toc::run(document, usize::from(*depth), *check),
+        Commands::Index { group_by, output } => commands::index::run(*group_by, output.as_deref()),
+        Commands::Lint { documents, fix } => commands::lint::run(documents, *fix),
+        Commands::Fmt { documents, check } => commands::fmt::run(documents, *check),
+        Commands::History { document } => commands::history::run(document),
+        Commands::Versions { document } => commands::versions::run(document),
+        Commands::Diff { document, rev } => commands::versions::run_diff(document, rev.as_deref()),
+        Commands::Restore { document, rev } => commands::versions::run_restore(document, rev),
+        Commands::Journal { date, action } => match action {
+            None => commands::journal::run(date.as_deref()),
+            Some(JournalAction::List { week }) => {
+                commands::journal::run_list(date.as_deref(), *week)
+            }
+        },
     };
 
     if let Err(e) = result {
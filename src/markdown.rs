@@ -0,0 +1,732 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::frontmatter;
+
+/// An ATX heading (`## Text`) of a markdown document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    /// Heading level, 1 to 6
+    pub level: usize,
+    /// Heading text with the `#` markers and any closing sequence removed
+    pub text: String,
+    /// Zero-based line index of the heading
+    pub line: usize,
+}
+
+/// If a line opens or closes a fenced code block, the fence it uses
+fn code_fence(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    ["```", "~~~"]
+        .into_iter()
+        .find(|fence| trimmed.starts_with(fence))
+}
+
+/// Parse a line as an ATX heading
+pub fn parse_heading(line: &str, index: usize) -> Option<Heading> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    // Drop an optional closing sequence of `#`s
+    let mut text = rest.trim();
+    let without_closing = text.trim_end_matches('#');
+    if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        text = without_closing.trim_end();
+    }
+
+    Some(Heading {
+        level,
+        text: text.to_string(),
+        line: index,
+    })
+}
+
+/// Mark which lines belong to fenced code blocks, fences included
+pub fn code_lines<S: AsRef<str>>(lines: &[S]) -> Vec<bool> {
+    let mut in_code = Vec::with_capacity(lines.len());
+    let mut open_fence: Option<&str> = None;
+    for line in lines {
+        match (open_fence, code_fence(line.as_ref())) {
+            (None, Some(fence)) => {
+                open_fence = Some(fence);
+                in_code.push(true);
+            }
+            (Some(open), Some(fence)) if open == fence => {
+                open_fence = None;
+                in_code.push(true);
+            }
+            (open, _) => in_code.push(open.is_some()),
+        }
+    }
+    in_code
+}
+
+/// All ATX headings of a document, skipping frontmatter and fenced code blocks
+pub fn headings(content: &str) -> Vec<Heading> {
+    let lines: Vec<&str> = content.lines().collect();
+    let in_code = code_lines(&lines);
+    lines
+        .iter()
+        .enumerate()
+        .skip(frontmatter::body_start(&lines))
+        .filter(|&(index, _)| !in_code[index])
+        .filter_map(|(index, line)| parse_heading(line, index))
+        .collect()
+}
+
+/// Reading speed used for reading time estimates
+pub const WORDS_PER_MINUTE: usize = 200;
+
+/// URL schemes recognized in autolinks and bare URLs
+const URL_SCHEMES: [&str; 3] = ["https://", "http://", "mailto:"];
+
+/// The kind of block a line of a document belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// Frontmatter, delimiters included
+    Frontmatter,
+    /// The opening or closing line of a fenced code block
+    Fence,
+    /// A line inside a fenced code block, with the language named on its opening fence
+    Code(Option<String>),
+    /// An ATX heading
+    Heading,
+    /// Any other line: paragraphs, lists, tables and quotes
+    Text,
+}
+
+/// Classify each line of a document by the block it belongs to
+pub fn blocks<S: AsRef<str>>(lines: &[S]) -> Vec<Block> {
+    let body_start = frontmatter::body_start(lines);
+    let mut result = Vec::with_capacity(lines.len());
+    let mut open: Option<(&str, Option<String>)> = None;
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.as_ref();
+        if index < body_start {
+            result.push(Block::Frontmatter);
+            continue;
+        }
+        let block = match (&open, code_fence(line)) {
+            (None, Some(fence)) => {
+                open = Some((fence, fence_language(line, fence)));
+                Block::Fence
+            }
+            (Some((open_fence, _)), Some(fence)) if *open_fence == fence => {
+                open = None;
+                Block::Fence
+            }
+            (Some((_, language)), _) => Block::Code(language.clone()),
+            (None, None) if parse_heading(line, index).is_some() => Block::Heading,
+            (None, None) => Block::Text,
+        };
+        result.push(block);
+    }
+    result
+}
+
+/// The language of each fenced code block of a document, None for blocks without one
+pub fn code_block_languages<S: AsRef<str>>(lines: &[S]) -> Vec<Option<String>> {
+    let mut languages = Vec::new();
+    let mut open = false;
+    for (line, block) in lines.iter().zip(blocks(lines)) {
+        if block != Block::Fence {
+            continue;
+        }
+        if !open {
+            let line = line.as_ref();
+            languages.push(code_fence(line).and_then(|fence| fence_language(line, fence)));
+        }
+        open = !open;
+    }
+    languages
+}
+
+/// The language named in the info string of an opening fence (`` ```rust,ignore ``)
+fn fence_language(line: &str, fence: &str) -> Option<String> {
+    let fence_char = fence.chars().next()?;
+    let language: String = line
+        .trim_start()
+        .trim_start_matches(fence_char)
+        .trim_start()
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != ',' && *c != '{')
+        .collect();
+    (!language.is_empty()).then_some(language)
+}
+
+/// Number of words in a document's prose and headings, leaving out frontmatter and
+/// code blocks
+pub fn word_count(content: &str) -> usize {
+    let lines: Vec<&str> = content.lines().collect();
+    lines
+        .iter()
+        .zip(blocks(&lines))
+        .filter(|(_, block)| matches!(block, Block::Text | Block::Heading))
+        .map(|(line, _)| {
+            line.split_whitespace()
+                .filter(|word| word.chars().any(char::is_alphanumeric))
+                .count()
+        })
+        .sum()
+}
+
+/// Estimated minutes to read a number of words, rounded up
+pub fn reading_minutes(words: usize) -> usize {
+    words.div_ceil(WORDS_PER_MINUTE)
+}
+
+/// Kinds of inline content within a line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Inline {
+    /// Plain text
+    Text,
+    /// The contents of a code span, without its backticks
+    Code,
+    /// The text of a link or the alt text of an image
+    LinkText,
+    /// The destination of a link or image
+    LinkTarget,
+    /// An autolink (`<https://...>`) or a bare URL
+    Url,
+    /// An HTML comment, delimiters included
+    Comment,
+}
+
+/// A classified byte range of a line
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineSpan {
+    pub kind: Inline,
+    pub range: Range<usize>,
+}
+
+/// Split a line of text into inline spans. Link and code span syntax is left out of
+/// the spans; `in_comment` carries an unterminated HTML comment over to the next line.
+pub fn inline_spans(line: &str, in_comment: &mut bool) -> Vec<InlineSpan> {
+    let mut spans = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < line.len() {
+        let rest = &line[i..];
+        if *in_comment || rest.starts_with("<!--") {
+            let search_from = if *in_comment { i } else { i + 4 };
+            let end = match line[search_from..].find("-->") {
+                Some(pos) => {
+                    *in_comment = false;
+                    search_from + pos + 3
+                }
+                None => {
+                    *in_comment = true;
+                    line.len()
+                }
+            };
+            push_span(&mut spans, text_start..i, Inline::Comment, i..end);
+            i = end;
+            text_start = end;
+            continue;
+        }
+
+        if rest.starts_with('`') {
+            let run = rest.chars().take_while(|&c| c == '`').count();
+            if let Some(close) = find_backtick_run(line, i + run, run) {
+                push_span(&mut spans, text_start..i, Inline::Code, i + run..close);
+                i = close + run;
+                text_start = i;
+            } else {
+                i += run;
+            }
+            continue;
+        }
+
+        let bracket = if rest.starts_with("![") {
+            Some(i + 1)
+        } else if rest.starts_with('[') {
+            Some(i)
+        } else {
+            None
+        };
+        if let Some((text, target, end)) = bracket.and_then(|open| parse_inline_link(line, open)) {
+            push_span(&mut spans, text_start..i, Inline::LinkText, text);
+            spans.push(InlineSpan {
+                kind: Inline::LinkTarget,
+                range: target,
+            });
+            i = end;
+            text_start = end;
+            continue;
+        }
+
+        if let Some(close) = rest.strip_prefix('<').and_then(|inner| {
+            URL_SCHEMES
+                .iter()
+                .any(|scheme| inner.starts_with(scheme))
+                .then(|| inner.find(|c: char| c == '>' || c.is_whitespace()))
+                .flatten()
+                .filter(|&pos| inner[pos..].starts_with('>'))
+        }) {
+            push_span(&mut spans, text_start..i, Inline::Url, i + 1..i + 1 + close);
+            i += close + 2;
+            text_start = i;
+            continue;
+        }
+
+        let at_word_start = line[..i]
+            .chars()
+            .next_back()
+            .map(|prev| !prev.is_alphanumeric())
+            .unwrap_or(true);
+        if at_word_start && URL_SCHEMES.iter().any(|scheme| rest.starts_with(scheme)) {
+            let end = i + bare_url_len(rest);
+            push_span(&mut spans, text_start..i, Inline::Url, i..end);
+            i = end;
+            text_start = end;
+            continue;
+        }
+
+        i += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+    }
+
+    push_span(&mut spans, text_start..line.len(), Inline::Text, 0..0);
+    spans
+}
+
+/// Push any pending text, then a span (empty spans are dropped)
+fn push_span(
+    spans: &mut Vec<InlineSpan>,
+    pending: Range<usize>,
+    kind: Inline,
+    range: Range<usize>,
+) {
+    for (kind, range) in [(Inline::Text, pending), (kind, range)] {
+        if !range.is_empty() {
+            spans.push(InlineSpan { kind, range });
+        }
+    }
+}
+
+/// The start of the next run of exactly `run` backticks at or after `from`
+fn find_backtick_run(line: &str, from: usize, run: usize) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let mut i = from;
+    while i < bytes.len() {
+        if bytes[i] == b'`' {
+            let len = bytes[i..].iter().take_while(|&&b| b == b'`').count();
+            if len == run {
+                return Some(i);
+            }
+            i += len;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Parse an inline link `[text](target "title")` whose `[` is at `open`, returning
+/// the ranges of the text and the target and the end of the link
+fn parse_inline_link(line: &str, open: usize) -> Option<(Range<usize>, Range<usize>, usize)> {
+    let bytes = line.as_bytes();
+    let close = matching_close(bytes, open, b'[', b']')?;
+    if bytes.get(close + 1) != Some(&b'(') {
+        return None;
+    }
+    let paren_close = matching_close(bytes, close + 1, b'(', b')')?;
+
+    // The destination ends at whitespace, before any title; `<...>` brackets are dropped
+    let inner = &line[close + 2..paren_close];
+    let leading = inner.len() - inner.trim_start().len();
+    let mut start = close + 2 + leading;
+    let mut end = line[start..paren_close]
+        .find(char::is_whitespace)
+        .map(|pos| start + pos)
+        .unwrap_or(paren_close);
+    if line[start..end].starts_with('<') && line[start..end].ends_with('>') && end - start >= 2 {
+        start += 1;
+        end -= 1;
+    }
+    Some((open + 1..close, start..end, paren_close + 1))
+}
+
+/// The index of the bracket closing the one at `open`, allowing nested pairs
+fn matching_close(bytes: &[u8], open: usize, open_char: u8, close_char: u8) -> Option<usize> {
+    let mut depth = 0;
+    for (offset, &b) in bytes[open..].iter().enumerate() {
+        if b == open_char {
+            depth += 1;
+        } else if b == close_char {
+            depth -= 1;
+            if depth == 0 {
+                return Some(open + offset);
+            }
+        }
+    }
+    None
+}
+
+/// Length of a bare URL at the start of `text`, leaving out trailing punctuation
+fn bare_url_len(text: &str) -> usize {
+    let url = text
+        .find(|c: char| c.is_whitespace() || c == '<')
+        .map(|end| &text[..end])
+        .unwrap_or(text);
+    let mut url = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '"']);
+    // A closing parenthesis belongs to the URL only if it opened one
+    while url.ends_with(')') && url.matches('(').count() < url.matches(')').count() {
+        url = url[..url.len() - 1].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+    }
+    url.len()
+}
+
+/// A section of a document: the headings leading to it and its anchor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    /// Zero-based line index of the section's heading
+    pub line: usize,
+    /// Text of the enclosing headings, outermost first, ending with the section's own
+    pub path: Vec<String>,
+    /// GitHub-compatible anchor of the section's heading
+    pub anchor: String,
+}
+
+/// The sections of a document in order, for looking up where a line belongs
+#[derive(Debug, Default)]
+pub struct Outline {
+    sections: Vec<Section>,
+}
+
+impl Outline {
+    /// Build the outline of a document from its headings
+    pub fn new(content: &str) -> Self {
+        let mut slugger = Slugger::new();
+        let mut stack: Vec<(usize, String)> = Vec::new();
+        let mut sections = Vec::new();
+        for heading in headings(content) {
+            while stack
+                .last()
+                .map(|(level, _)| *level >= heading.level)
+                .unwrap_or(false)
+            {
+                stack.pop();
+            }
+            stack.push((heading.level, plain_text(&heading.text)));
+            sections.push(Section {
+                line: heading.line,
+                path: stack.iter().map(|(_, text)| text.clone()).collect(),
+                anchor: slugger.slug(&heading.text),
+            });
+        }
+        Self { sections }
+    }
+
+    /// The section containing a (zero-based) line, if the line follows any heading
+    pub fn section_at(&self, line: usize) -> Option<&Section> {
+        let idx = self
+            .sections
+            .partition_point(|section| section.line <= line);
+        idx.checked_sub(1).map(|idx| &self.sections[idx])
+    }
+}
+
+/// Strip inline markdown (links, images, code spans, emphasis) from heading text,
+/// leaving the text as it is rendered
+pub fn plain_text(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '`' | '*' | '[' => {}
+            '!' if chars.get(i) == Some(&'[') => {}
+            ']' => {
+                if chars.get(i) == Some(&'(') {
+                    // Skip the link target
+                    while i < chars.len() {
+                        i += 1;
+                        if chars[i - 1] == ')' {
+                            break;
+                        }
+                    }
+                }
+            }
+            '_' | '~' => {
+                // Emphasis and strikethrough delimiters open or close a word, while
+                // underscores inside a word (`snake_case`) are text
+                let start = i - 1;
+                while chars.get(i) == Some(&c) {
+                    i += 1;
+                }
+                let word_before = start > 0 && chars[start - 1].is_alphanumeric();
+                let word_after = chars.get(i).is_some_and(|next| next.is_alphanumeric());
+                // A single tilde is text (`~5 min`), strikethrough takes two
+                let text = (c == '~' && i - start < 2) || (word_before && word_after);
+                if text {
+                    result.extend(&chars[start..i]);
+                }
+            }
+            c => result.push(c),
+        }
+    }
+    result.trim().to_string()
+}
+
+/// Generates GitHub-compatible anchor slugs, numbering repeated headings
+/// (`intro`, `intro-1`, `intro-2`, ...)
+#[derive(Debug, Default)]
+pub struct Slugger {
+    seen: HashMap<String, usize>,
+}
+
+impl Slugger {
+    /// Create a slugger that has not seen any headings yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The anchor of the next heading with the given text
+    pub fn slug(&mut self, text: &str) -> String {
+        let base = slugify(text);
+        let mut slug = base.clone();
+        // A generated slug such as `intro-1` may itself be the text of another heading
+        while self.seen.contains_key(&slug) {
+            let count = self.seen.entry(base.clone()).or_insert(0);
+            *count += 1;
+            slug = format!("{}-{}", base, count);
+        }
+        self.seen.insert(slug.clone(), 0);
+        slug
+    }
+}
+
+/// Convert heading text into an anchor the way GitHub does: rendered text, lowercased,
+/// with punctuation removed and spaces turned into hyphens
+pub fn slugify(text: &str) -> String {
+    plain_text(text)
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headings() {
+        let content = "---\n# comment\n---\n# Title\n\n## Setup ##\n```sh\n# not a heading\n```\n#hashtag\n    # indented code\n### C# notes\n";
+        let found = headings(content);
+        let summary: Vec<(usize, &str, usize)> = found
+            .iter()
+            .map(|h| (h.level, h.text.as_str(), h.line))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(1, "Title", 3), (2, "Setup", 5), (3, "C# notes", 11)]
+        );
+    }
+
+    #[test]
+    fn test_code_lines() {
+        let lines = ["text", "```rust", "code", "~~~", "```", "after"];
+        assert_eq!(
+            code_lines(&lines),
+            vec![false, true, true, true, true, false]
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let lines = [
+            "---",
+            "title: x",
+            "---",
+            "# Title",
+            "text",
+            "```rust,ignore",
+            "let x = 1;",
+            "```",
+            "~~~",
+            "plain",
+            "~~~",
+        ];
+        assert_eq!(
+            blocks(&lines),
+            vec![
+                Block::Frontmatter,
+                Block::Frontmatter,
+                Block::Frontmatter,
+                Block::Heading,
+                Block::Text,
+                Block::Fence,
+                Block::Code(Some(String::from("rust"))),
+                Block::Fence,
+                Block::Fence,
+                Block::Code(None),
+                Block::Fence,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_code_block_languages() {
+        let lines = [
+            "```rust",
+            "fn main() {}",
+            "```",
+            "",
+            "~~~",
+            "~~~",
+            "```py title",
+            "```",
+        ];
+        assert_eq!(
+            code_block_languages(&lines),
+            vec![Some(String::from("rust")), None, Some(String::from("py"))]
+        );
+    }
+
+    #[test]
+    fn test_word_count() {
+        let content = "---\ntitle: Not counted\n---\n# Two words\n\nThree more words - here.\n\n```\nno code words\n```\n";
+        assert_eq!(word_count(content), 6);
+        assert_eq!(word_count(""), 0);
+    }
+
+    #[test]
+    fn test_reading_minutes() {
+        assert_eq!(reading_minutes(0), 0);
+        assert_eq!(reading_minutes(1), 1);
+        assert_eq!(reading_minutes(400), 2);
+        assert_eq!(reading_minutes(401), 3);
+    }
+
+    fn spans_of<'a>(line: &'a str, in_comment: &mut bool) -> Vec<(Inline, &'a str)> {
+        inline_spans(line, in_comment)
+            .into_iter()
+            .map(|span| (span.kind, &line[span.range]))
+            .collect()
+    }
+
+    #[test]
+    fn test_inline_spans() {
+        let mut in_comment = false;
+        let line = "Run `cargo` per [the guide](docs/guide.md \"Guide\"), see <https://a.io> or https://b.io/x).";
+        assert_eq!(
+            spans_of(line, &mut in_comment),
+            vec![
+                (Inline::Text, "Run "),
+                (Inline::Code, "cargo"),
+                (Inline::Text, " per "),
+                (Inline::LinkText, "the guide"),
+                (Inline::LinkTarget, "docs/guide.md"),
+                (Inline::Text, ", see "),
+                (Inline::Url, "https://a.io"),
+                (Inline::Text, " or "),
+                (Inline::Url, "https://b.io/x"),
+                (Inline::Text, ")."),
+            ]
+        );
+        assert_eq!(
+            spans_of("![alt](img.png) [not a link] `open", &mut in_comment),
+            vec![
+                (Inline::LinkText, "alt"),
+                (Inline::LinkTarget, "img.png"),
+                (Inline::Text, " [not a link] `open"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inline_spans_comments() {
+        let mut in_comment = false;
+        assert_eq!(
+            spans_of("a <!-- b --> c <!-- d", &mut in_comment),
+            vec![
+                (Inline::Text, "a "),
+                (Inline::Comment, "<!-- b -->"),
+                (Inline::Text, " c "),
+                (Inline::Comment, "<!-- d"),
+            ]
+        );
+        assert!(in_comment);
+        assert_eq!(
+            spans_of("e --> f", &mut in_comment),
+            vec![(Inline::Comment, "e -->"), (Inline::Text, " f")]
+        );
+        assert!(!in_comment);
+    }
+
+    #[test]
+    fn test_outline_section_at() {
+        let content = "Intro\n# Guide\n## Setup\n### Linux\ntext\n## Use `it`\nmore\n";
+        let outline = Outline::new(content);
+        assert_eq!(outline.section_at(0), None);
+
+        let section = outline.section_at(4).unwrap();
+        assert_eq!(section.path, vec!["Guide", "Setup", "Linux"]);
+        assert_eq!(section.anchor, "linux");
+
+        let section = outline.section_at(6).unwrap();
+        assert_eq!(section.path, vec!["Guide", "Use it"]);
+        assert_eq!(section.anchor, "use-it");
+        // A heading line belongs to its own section
+        assert_eq!(outline.section_at(2).unwrap().anchor, "setup");
+    }
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(
+            plain_text("Use `cargo` with [the docs](https://doc.rust-lang.org) **now**"),
+            "Use cargo with the docs now"
+        );
+        assert_eq!(plain_text("![logo](logo.png) Brand"), "logo Brand");
+        assert_eq!(plain_text("Array [0]"), "Array 0");
+        assert_eq!(
+            plain_text("The _best_ __bold__ ~~old~~ snake_case way"),
+            "The best bold old snake_case way"
+        );
+        assert_eq!(plain_text("Takes ~5 min"), "Takes ~5 min");
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Getting Started"), "getting-started");
+        assert_eq!(slugify("What's new in v2.0?"), "whats-new-in-v20");
+        assert_eq!(slugify("snake_case & more"), "snake_case--more");
+        assert_eq!(slugify("Über `code`"), "über-code");
+        assert_eq!(slugify("The _best_ way"), "the-best-way");
+    }
+
+    #[test]
+    fn test_slugger_numbers_duplicates() {
+        let mut slugger = Slugger::new();
+        assert_eq!(slugger.slug("Intro"), "intro");
+        assert_eq!(slugger.slug("Intro"), "intro-1");
+        assert_eq!(slugger.slug("Intro 1"), "intro-1-1");
+        assert_eq!(slugger.slug("Intro"), "intro-2");
+
+        let mut slugger = Slugger::new();
+        assert_eq!(slugger.slug("A"), "a");
+        assert_eq!(slugger.slug("A-1"), "a-1");
+        assert_eq!(slugger.slug("A"), "a-2");
+    }
+}
@@ -0,0 +1,154 @@
+use std::io;
+
+use crate::commands::list::{DocumentEntry, DocumentType};
+use crate::frontmatter;
+
+/// A single condition of a document query
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    /// `tag:<tag>` — the `tags` list contains the tag (case-insensitive)
+    Tag(String),
+    /// `status:<status>` — the `status` key equals the value (case-insensitive)
+    Status(String),
+    /// `type:doc|template` — the kind of document
+    Type(DocumentType),
+    /// `path:<text>` — the path relative to the library root contains the text
+    Path(String),
+    /// `title:<text>` — the title contains the text
+    Title(String),
+    /// Bare text — the content contains the text
+    Text(String),
+}
+
+/// A document query such as `tag:rust status:draft "error handling"`.
+///
+/// All terms must match. Values are compared case-insensitively, and double quotes group
+/// words containing spaces into one term.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    /// Parse a query string
+    pub fn parse(query: &str) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidInput, msg);
+
+        let mut terms = Vec::new();
+        for word in split_words(query) {
+            let term = match word.split_once(':') {
+                Some((field, value)) if !value.is_empty() => {
+                    let value = value.to_lowercase();
+                    match field.to_lowercase().as_str() {
+                        "tag" => Term::Tag(value),
+                        "status" => Term::Status(value),
+                        "path" => Term::Path(value),
+                        "title" => Term::Title(value),
+                        "type" => match value.as_str() {
+                            "doc" | "document" => Term::Type(DocumentType::Document),
+                            "template" => Term::Type(DocumentType::Template),
+                            _ => return Err(invalid(format!("Unknown document type: {}", value))),
+                        },
+                        _ => Term::Text(word.to_lowercase()),
+                    }
+                }
+                _ => Term::Text(word.to_lowercase()),
+            };
+            terms.push(term);
+        }
+
+        if terms.is_empty() {
+            return Err(invalid(String::from("Empty query")));
+        }
+        Ok(Self { terms })
+    }
+
+    /// Whether a document with the given content matches every term
+    pub fn matches(&self, doc: &DocumentEntry, content: &str) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Tag(tag) => frontmatter::get_list(content, "tags")
+                .unwrap_or_default()
+                .iter()
+                .any(|t| t.to_lowercase() == *tag),
+            Term::Status(status) => frontmatter::get(content, "status")
+                .map(|s| s.to_lowercase() == *status)
+                .unwrap_or(false),
+            Term::Type(doc_type) => doc.doc_type == *doc_type,
+            Term::Path(text) => doc.path.to_lowercase().contains(text.as_str()),
+            Term::Title(text) => doc.title.to_lowercase().contains(text.as_str()),
+            Term::Text(text) => content.to_lowercase().contains(text.as_str()),
+        })
+    }
+}
+
+/// Split a query into words, keeping double-quoted sections together
+fn split_words(query: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in query.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, title: &str, doc_type: DocumentType) -> DocumentEntry {
+        DocumentEntry {
+            path: path.to_string(),
+            title: title.to_string(),
+            doc_type,
+        }
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words(r#"tag:rust "error handling"  title:"Getting started""#),
+            vec!["tag:rust", "error handling", "title:Getting started"]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        assert!(Query::parse("   ").is_err());
+        assert!(Query::parse("type:unknown").is_err());
+    }
+
+    #[test]
+    fn test_matches_fields() {
+        let doc = entry("docs/rust/guide.md", "Rust Guide", DocumentType::Document);
+        let content =
+            "---\ntags: [Rust, cli]\nstatus: draft\n---\n# Rust Guide\n\nError handling.\n";
+
+        let matches = |q: &str| Query::parse(q).unwrap().matches(&doc, content);
+        assert!(matches("tag:rust"));
+        assert!(matches("status:DRAFT type:doc"));
+        assert!(matches("path:docs/rust title:guide"));
+        assert!(matches("\"error handling\""));
+        assert!(!matches("tag:python"));
+        assert!(!matches("tag:rust status:published"));
+        assert!(!matches("type:template"));
+    }
+
+    #[test]
+    fn test_unknown_field_is_text() {
+        let doc = entry("a.md", "A", DocumentType::Document);
+        let query = Query::parse("see:https").unwrap();
+        assert!(query.matches(&doc, "# A\n\nsee:https://example.com\n"));
+    }
+}
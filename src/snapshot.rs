@@ -0,0 +1,305 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::STATE_DIR;
+use crate::hash::sha256_hex;
+
+/// Directory (inside the state directory) holding snapshot contents, named by hash
+const OBJECTS_DIR: &str = "objects";
+
+/// Directory (inside the state directory) holding one version log per document
+const VERSIONS_DIR: &str = "versions";
+
+/// Minimum length of a hash prefix accepted as a revision
+const MIN_REV_LEN: usize = 4;
+
+/// Length of a SHA-256 hash in hex
+const HASH_LEN: usize = 64;
+
+/// A stored snapshot of a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Version {
+    /// SHA-256 of the snapshot content
+    pub hash: String,
+    /// When the snapshot was taken, as a Unix timestamp
+    pub timestamp: i64,
+}
+
+impl Version {
+    /// Abbreviated hash used as the revision name
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(8)]
+    }
+}
+
+/// Path of a document relative to the library root, as used for its version log
+fn relative_key(lib_root: &Path, path: &Path) -> io::Result<PathBuf> {
+    let lib_canonical = lib_root.canonicalize()?;
+    let canonical = path.canonicalize()?;
+    canonical
+        .strip_prefix(&lib_canonical)
+        .map(|p| p.to_path_buf())
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Access denied: path is outside the library directory",
+            )
+        })
+}
+
+/// Location of the version log of a document
+fn log_path(lib_root: &Path, path: &Path) -> io::Result<PathBuf> {
+    let key = relative_key(lib_root, path)?;
+    let mut log = lib_root.join(STATE_DIR).join(VERSIONS_DIR).join(key);
+    let file_name = format!(
+        "{}.log",
+        log.file_name().unwrap_or_default().to_string_lossy()
+    );
+    log.set_file_name(file_name);
+    Ok(log)
+}
+
+/// Whether a string is a full SHA-256 hash in hex
+fn is_valid_hash(hash: &str) -> bool {
+    hash.len() == HASH_LEN && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Location of a stored object, fanned out by the first two hash characters
+fn object_path(lib_root: &Path, hash: &str) -> io::Result<PathBuf> {
+    if !is_valid_hash(hash) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid snapshot hash: {}", hash),
+        ));
+    }
+    let (fan_out, rest) = hash.split_at(2);
+    Ok(lib_root
+        .join(STATE_DIR)
+        .join(OBJECTS_DIR)
+        .join(fan_out)
+        .join(rest))
+}
+
+/// Snapshot the current content of a document before it is modified.
+///
+/// Content is stored once per distinct hash, and nothing is recorded if the document
+/// is unchanged since its last snapshot. Returns the new version, if one was recorded.
+pub fn record(lib_root: &Path, path: &Path) -> io::Result<Option<Version>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read(path)?;
+    let hash = sha256_hex(&content);
+
+    let existing = versions(lib_root, path)?;
+    if existing.last().map(|v| v.hash == hash).unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let object = object_path(lib_root, &hash)?;
+    if !object.exists() {
+        if let Some(parent) = object.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&object, &content)?;
+    }
+
+    let version = Version {
+        hash,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0),
+    };
+
+    let log = log_path(lib_root, path)?;
+    if let Some(parent) = log.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&log)?;
+    writeln!(file, "{}\t{}", version.timestamp, version.hash)?;
+
+    Ok(Some(version))
+}
+
+/// All recorded versions of a document, oldest first
+pub fn versions(lib_root: &Path, path: &Path) -> io::Result<Vec<Version>> {
+    let log = log_path(lib_root, path)?;
+    if !log.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&log)?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(i, line)| {
+            let version = parse_log_line(line);
+            if version.is_none() {
+                eprintln!(
+                    "Warning: skipping malformed line {} in {}",
+                    i + 1,
+                    log.display()
+                );
+            }
+            version
+        })
+        .collect())
+}
+
+/// Parse a version log line (`<timestamp>\t<hash>`)
+fn parse_log_line(line: &str) -> Option<Version> {
+    let (timestamp, hash) = line.split_once('\t')?;
+    let hash = hash.trim();
+    if !is_valid_hash(hash) {
+        return None;
+    }
+    Some(Version {
+        hash: hash.to_string(),
+        timestamp: timestamp.trim().parse().ok()?,
+    })
+}
+
+/// Find the version of a document named by a hash prefix, or the latest version if
+/// no revision is given
+pub fn resolve(lib_root: &Path, path: &Path, rev: Option<&str>) -> io::Result<Version> {
+    let mut versions = versions(lib_root, path)?;
+    let Some(rev) = rev else {
+        return versions.pop().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No versions recorded for: {}", path.display()),
+            )
+        });
+    };
+
+    if rev.len() < MIN_REV_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Revision '{}' is too short (use at least {} characters)",
+                rev, MIN_REV_LEN
+            ),
+        ));
+    }
+
+    let rev = rev.to_lowercase();
+    let mut matching: Vec<Version> = versions
+        .into_iter()
+        .filter(|v| v.hash.starts_with(&rev))
+        .collect();
+    // The same content may have been recorded more than once
+    matching.dedup_by(|a, b| a.hash == b.hash);
+
+    match matching.len() {
+        0 => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Unknown revision: {}", rev),
+        )),
+        1 => Ok(matching.remove(0)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Ambiguous revision: {}", rev),
+        )),
+    }
+}
+
+/// Read the content of a stored version
+pub fn read(lib_root: &Path, version: &Version) -> io::Result<String> {
+    fs::read_to_string(object_path(lib_root, &version.hash)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn setup(name: &str) -> (PathBuf, PathBuf) {
+        let temp_dir = env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs")).unwrap();
+        let doc = temp_dir.join("docs/note.md");
+        fs::write(&doc, "# Version 1\n").unwrap();
+        (temp_dir, doc)
+    }
+
+    #[test]
+    fn test_record_and_read() {
+        let (lib, doc) = setup("mdlibs_test_snapshot_record");
+
+        let first = record(&lib, &doc).unwrap().unwrap();
+        // Unchanged content is not recorded twice
+        assert_eq!(record(&lib, &doc).unwrap(), None);
+
+        fs::write(&doc, "# Version 2\n").unwrap();
+        let second = record(&lib, &doc).unwrap().unwrap();
+
+        let all = versions(&lib, &doc).unwrap();
+        assert_eq!(all, vec![first.clone(), second]);
+        assert_eq!(read(&lib, &first).unwrap(), "# Version 1\n");
+        assert!(lib.join(".mdlibs/versions/docs/note.md.log").exists());
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
+
+    #[test]
+    fn test_versions_skips_malformed_lines() {
+        let (lib, doc) = setup("mdlibs_test_snapshot_malformed");
+        let first = record(&lib, &doc).unwrap().unwrap();
+        let log = lib.join(".mdlibs/versions/docs/note.md.log");
+        let mut content = fs::read_to_string(&log).unwrap();
+        content.push_str("123\tab\nnot a version\n456\tzz\n");
+        fs::write(&log, content).unwrap();
+
+        assert_eq!(versions(&lib, &doc).unwrap(), vec![first]);
+        let short = Version {
+            hash: String::from("a"),
+            timestamp: 0,
+        };
+        assert_eq!(
+            read(&lib, &short).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let (lib, doc) = setup("mdlibs_test_snapshot_resolve");
+        let first = record(&lib, &doc).unwrap().unwrap();
+        fs::write(&doc, "# Version 2\n").unwrap();
+        let second = record(&lib, &doc).unwrap().unwrap();
+
+        assert_eq!(resolve(&lib, &doc, None).unwrap(), second);
+        assert_eq!(
+            resolve(&lib, &doc, Some(first.short_hash())).unwrap(),
+            first
+        );
+        assert!(resolve(&lib, &doc, Some("ab")).is_err());
+        assert_eq!(
+            resolve(&lib, &doc, Some("zzzzzz")).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
+
+    #[test]
+    fn test_resolve_without_versions() {
+        let (lib, doc) = setup("mdlibs_test_snapshot_empty");
+        assert_eq!(
+            resolve(&lib, &doc, None).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&lib);
+    }
+}
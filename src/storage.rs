@@ -0,0 +1,194 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::STATE_DIR;
+use crate::hash::sha256_hex;
+use crate::snapshot;
+
+/// Name of the advisory lock file inside the state directory
+const LOCK_FILE: &str = "lock";
+
+/// A document as read from disk, remembered so a later write can detect whether
+/// someone else changed the file in the meantime
+#[derive(Debug)]
+pub struct LoadedDocument {
+    pub path: PathBuf,
+    pub content: String,
+    hash: String,
+}
+
+/// Read a document for modification
+pub fn read_document(path: &Path) -> io::Result<LoadedDocument> {
+    let content = fs::read_to_string(path)?;
+    let hash = sha256_hex(content.as_bytes());
+    Ok(LoadedDocument {
+        path: path.to_path_buf(),
+        content,
+        hash,
+    })
+}
+
+/// Replace the content of a document read with `read_document`.
+///
+/// Fails without writing if the file changed on disk since it was read. Otherwise the
+/// previous content is snapshotted and the new content written atomically.
+pub fn write_document(lib_root: &Path, doc: &LoadedDocument, content: &str) -> io::Result<()> {
+    let current = fs::read(&doc.path)?;
+    if sha256_hex(&current) != doc.hash {
+        return Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            format!(
+                "Document changed on disk since it was read, not overwriting: {}",
+                doc.path.display()
+            ),
+        ));
+    }
+
+    snapshot::record(lib_root, &doc.path)?;
+    write_atomic(&doc.path, content)
+}
+
+/// Write a file by writing a temporary file in the same directory and renaming it over
+/// the target, so readers (and crashes) never see a partially written file
+pub fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = dir.join(format!(".{}.mdlibs-tmp.{}", file_name, std::process::id()));
+
+    let result = (|| {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        // Keep the permissions of the file being replaced
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Advisory lock on a library, held while mdlibs modifies several files at once.
+/// The lock is released when the value is dropped.
+#[derive(Debug)]
+pub struct LibraryLock {
+    path: PathBuf,
+}
+
+impl LibraryLock {
+    /// Acquire the lock, failing if another mdlibs process holds it
+    pub fn acquire(lib_root: &Path) -> io::Result<Self> {
+        let state_dir = lib_root.join(STATE_DIR);
+        fs::create_dir_all(&state_dir)?;
+        let path = state_dir.join(LOCK_FILE);
+
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                writeln!(file, "{}", std::process::id())?;
+                Ok(Self { path })
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                let owner = fs::read_to_string(&path).unwrap_or_default();
+                Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    format!(
+                        "Library is locked by another mdlibs process (pid {}). \
+                         If no other mdlibs is running, remove {}",
+                        owner.trim(),
+                        path.display()
+                    ),
+                ))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Drop for LibraryLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn setup(name: &str) -> PathBuf {
+        let temp_dir = env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_content() {
+        let dir = setup("mdlibs_test_storage_atomic");
+        let path = dir.join("doc.md");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_write_document_snapshots_previous_content() {
+        let dir = setup("mdlibs_test_storage_write");
+        let path = dir.join("doc.md");
+        fs::write(&path, "# One\n").unwrap();
+
+        let doc = read_document(&path).unwrap();
+        write_document(&dir, &doc, "# Two\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# Two\n");
+
+        let versions = snapshot::versions(&dir, &path).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(snapshot::read(&dir, &versions[0]).unwrap(), "# One\n");
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_write_document_detects_concurrent_change() {
+        let dir = setup("mdlibs_test_storage_conflict");
+        let path = dir.join("doc.md");
+        fs::write(&path, "# One\n").unwrap();
+
+        let doc = read_document(&path).unwrap();
+        fs::write(&path, "# Edited elsewhere\n").unwrap();
+
+        let err = write_document(&dir, &doc, "# Two\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# Edited elsewhere\n");
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_library_lock_is_exclusive() {
+        let dir = setup("mdlibs_test_storage_lock");
+
+        let lock = LibraryLock::acquire(&dir).unwrap();
+        let err = LibraryLock::acquire(&dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+
+        drop(lock);
+        assert!(LibraryLock::acquire(&dir).is_ok());
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
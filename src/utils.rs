@@ -1,5 +1,11 @@
+use std::env;
 use std::fs;
-use std::path::Path;
+use std::io::{self, IsTerminal};
+use std::path::{Component, Path};
+use std::process::{Command, Stdio};
+
+use crate::frontmatter;
+use crate::markdown;
 
 /// Check if a path is a markdown file
 pub fn is_markdown_file(path: &Path) -> bool {
@@ -9,9 +15,18 @@ pub fn is_markdown_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-/// Extract the title (first H1 heading) from markdown content
+/// Extract the title (first H1 heading, else a frontmatter `title`) from markdown content
 pub fn extract_title_from_content(content: &str) -> Option<String> {
-    for line in content.lines() {
+    let lines: Vec<&str> = content.lines().collect();
+    let in_code = markdown::code_lines(&lines);
+    // Skip frontmatter and code blocks, whose comments also start with "# "
+    let body_start = frontmatter::body_start(&lines);
+    for (line, _) in lines
+        .iter()
+        .zip(&in_code)
+        .skip(body_start)
+        .filter(|(_, &code)| !code)
+    {
         let line = line.trim();
         if let Some(title_text) = line.strip_prefix("# ") {
             let trimmed_title = title_text.trim();
@@ -21,7 +36,9 @@ pub fn extract_title_from_content(content: &str) -> Option<String> {
             }
         }
     }
-    None
+    frontmatter::get(content, "title")
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
 }
 
 /// Extract the title from a markdown file
@@ -41,6 +58,187 @@ pub fn truncate_display(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Shorten a string to at most `width` characters, ending it with "..." when cut
+pub fn truncate_to_width(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        return s.to_string();
+    }
+    if width <= 3 {
+        return ".".repeat(width);
+    }
+    let truncated: String = s.chars().take(width - 3).collect();
+    format!("{}...", truncated)
+}
+
+/// Format a byte count for display (`512 B`, `1.5 KB`, `2.0 MB`)
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Width of the terminal standard output is written to, or None when output is
+/// redirected. `COLUMNS` takes precedence, then `stty size`, then 80 columns.
+pub fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    if let Some(columns) = env::var("COLUMNS")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .filter(|&columns: &usize| columns > 0)
+    {
+        return Some(columns);
+    }
+    // `stty size` prints "rows columns" for the terminal on its standard input
+    let columns = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .nth(1)
+                .and_then(|columns| columns.parse().ok())
+        })
+        .filter(|&columns: &usize| columns > 0);
+    Some(columns.unwrap_or(80))
+}
+
+/// Replace the lines between a pair of marker comments (e.g. `<!-- toc -->` and
+/// `<!-- /toc -->`) with `body`, returning None if either marker is missing
+pub fn replace_marked_block(content: &str, start: &str, end: &str, body: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    // Markers shown inside code blocks are examples, not the block to replace
+    let in_code = markdown::code_lines(&lines);
+    let is_marker = |idx: usize, marker: &str| !in_code[idx] && lines[idx].trim() == marker;
+    let start_idx = (0..lines.len()).find(|&idx| is_marker(idx, start))?;
+    let end_idx = (start_idx..lines.len()).find(|&idx| is_marker(idx, end))?;
+
+    let mut result: Vec<&str> = lines[..=start_idx].to_vec();
+    result.extend(body.lines());
+    result.extend_from_slice(&lines[end_idx..]);
+
+    let mut output = result.join("\n");
+    // Preserve the original trailing newline behavior
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    Some(output)
+}
+
+/// Replace the lines between a pair of marker comments with `body`, inserting the
+/// markers after the document title if they are missing
+pub fn set_marked_block(content: &str, start: &str, end: &str, body: &str) -> String {
+    if let Some(updated) = replace_marked_block(content, start, end, body) {
+        return updated;
+    }
+
+    let block = if body.is_empty() {
+        format!("{}\n{}", start, end)
+    } else {
+        format!("{}\n{}\n{}", start, body, end)
+    };
+    insert_after_title(content, &block)
+}
+
+/// Insert a block after the document title (first H1 heading), separated by a blank
+/// line, or at the start of the body if the document has no title
+fn insert_after_title(content: &str, block: &str) -> String {
+    let had_trailing_newline = content.ends_with('\n');
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    let body_start = frontmatter::body_start(&lines);
+    let in_code = markdown::code_lines(&lines);
+    match (body_start..lines.len())
+        .find(|&idx| !in_code[idx] && lines[idx].trim().starts_with("# "))
+    {
+        Some(title_idx) => {
+            lines.insert(title_idx + 1, String::new());
+            lines.insert(title_idx + 2, block.to_string());
+        }
+        None => {
+            lines.insert(body_start, String::new());
+            lines.insert(body_start, block.to_string());
+        }
+    }
+
+    let mut result = lines.join("\n");
+    if had_trailing_newline {
+        result.push('\n');
+    }
+    result
+}
+
+/// Build a relative link (with `/` separators) from the directory `from_dir` to `to`
+pub fn relative_link(from_dir: &Path, to: &Path) -> String {
+    let from: Vec<Component> = from_dir.components().collect();
+    let to_components: Vec<Component> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(&to_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = vec![String::from(".."); from.len() - common];
+    parts.extend(
+        to_components[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+/// Score how well `query` fuzzily matches `candidate` (case-insensitive).
+///
+/// Returns None unless every character of the query appears in the candidate in order.
+/// Contiguous substrings score highest, then runs of consecutive characters and
+/// characters at the start of a word.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let candidate_lower = candidate.to_lowercase();
+    let chars: Vec<char> = candidate_lower.chars().collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0i64;
+    let mut pos = 0;
+    let mut prev_match: Option<usize> = None;
+    for &qc in &query {
+        let idx = pos + chars[pos..].iter().position(|&c| c == qc)?;
+        score += 1;
+        if prev_match.map(|p| p + 1 == idx).unwrap_or(false) {
+            score += 5;
+        }
+        if idx == 0 || !chars[idx - 1].is_alphanumeric() {
+            score += 3;
+        }
+        prev_match = Some(idx);
+        pos = idx + 1;
+    }
+
+    let query_text: String = query.iter().collect();
+    if candidate_lower == query_text {
+        score += 100;
+    } else if candidate_lower.contains(&query_text) {
+        score += 50;
+    }
+    // Prefer shorter candidates when everything else is equal
+    score -= chars.len() as i64 / 10;
+    Some(score)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,6 +262,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_insert_after_title() {
+        assert_eq!(
+            insert_after_title("# Title\n\nBody\n", "BLOCK"),
+            "# Title\n\nBLOCK\n\nBody\n"
+        );
+        assert_eq!(
+            insert_after_title("---\na: b\n---\nBody\n", "BLOCK"),
+            "---\na: b\n---\nBLOCK\n\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_title_skips_frontmatter() {
+        let content = "---\n# comment\ntags: [a]\n---\n# Real Title\n";
+        assert_eq!(
+            extract_title_from_content(content),
+            Some("Real Title".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_title_falls_back_to_frontmatter() {
+        let content = "---\ntitle: \"From Frontmatter\"\n---\nNo heading\n";
+        assert_eq!(
+            extract_title_from_content(content),
+            Some("From Frontmatter".to_string())
+        );
+        // A heading wins over the frontmatter title
+        let content = "---\ntitle: Other\n---\n# Heading\n";
+        assert_eq!(
+            extract_title_from_content(content),
+            Some("Heading".to_string())
+        );
+    }
+
     #[test]
     fn test_extract_title_from_content_none() {
         let content = "No title here";
@@ -108,4 +342,96 @@ mod tests {
         let unicode_str = "日本語テスト";
         assert_eq!(truncate_display(unicode_str, 3), "日本語...");
     }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("a much longer title", 10), "a much ...");
+        assert_eq!(truncate_to_width("日本語テスト", 5), "日本...");
+        assert_eq!(truncate_to_width("abcdef", 2), "..");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[test]
+    fn test_replace_marked_block() {
+        let content = "# Doc\n<!-- toc -->\nold\n<!-- /toc -->\nbody\n";
+        let result = replace_marked_block(content, "<!-- toc -->", "<!-- /toc -->", "new\nlines");
+        assert_eq!(
+            result,
+            Some("# Doc\n<!-- toc -->\nnew\nlines\n<!-- /toc -->\nbody\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_marked_block_skips_code_blocks() {
+        let content =
+            "# Doc\n\n```markdown\n# Example\n<!-- toc -->\nsample\n<!-- /toc -->\n```\n\nbody\n";
+        assert_eq!(
+            replace_marked_block(content, "<!-- toc -->", "<!-- /toc -->", "new"),
+            None
+        );
+        let updated = set_marked_block(content, "<!-- toc -->", "<!-- /toc -->", "new");
+        assert!(updated.starts_with("# Doc\n\n<!-- toc -->\nnew\n<!-- /toc -->\n"));
+        assert!(updated.contains("<!-- toc -->\nsample\n<!-- /toc -->\n```"));
+
+        // A `#` comment in a code block before the title is not the title
+        let content = "```sh\n# install\n```\n# Title\n";
+        assert_eq!(
+            extract_title_from_content(content).as_deref(),
+            Some("Title")
+        );
+        let updated = set_marked_block(content, "<!-- toc -->", "<!-- /toc -->", "");
+        assert_eq!(
+            updated,
+            "```sh\n# install\n```\n# Title\n\n<!-- toc -->\n<!-- /toc -->\n"
+        );
+    }
+
+    #[test]
+    fn test_replace_marked_block_missing_markers() {
+        let content = "# Doc\n<!-- toc -->\nno end marker";
+        assert_eq!(
+            replace_marked_block(content, "<!-- toc -->", "<!-- /toc -->", "x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_relative_link() {
+        assert_eq!(
+            relative_link(
+                Path::new("journal/2026/10"),
+                Path::new("journal/2026/10/a.md")
+            ),
+            "a.md"
+        );
+        assert_eq!(
+            relative_link(
+                Path::new("journal/2026/10"),
+                Path::new("journal/2025/12/b.md")
+            ),
+            "../../2025/12/b.md"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_score_requires_subsequence() {
+        assert!(fuzzy_score("rdme", "docs/README.md").is_some());
+        assert!(fuzzy_score("xyz", "docs/README.md").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_score_ranking() {
+        let exact = fuzzy_score("setup", "Setup").unwrap();
+        let substring = fuzzy_score("setup", "Linux Setup Guide").unwrap();
+        let scattered = fuzzy_score("setup", "Server etiquette update").unwrap();
+        assert!(exact > substring);
+        assert!(substring > scattered);
+    }
 }
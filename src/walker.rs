@@ -0,0 +1,519 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use crate::config::{WalkConfig, DOCS_DIR, TEMPLATES_DIR};
+use crate::ignore::Rules;
+use crate::parallel;
+use crate::utils::is_markdown_file;
+
+/// Type of document
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DocumentType {
+    Document,
+    Template,
+}
+
+impl std::fmt::Display for DocumentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentType::Document => f.pad("doc"),
+            DocumentType::Template => f.pad("template"),
+        }
+    }
+}
+
+/// A markdown document found by the walker
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentHandle {
+    pub doc_type: DocumentType,
+    /// Directory the walk started from, relative to the library root (empty for the root)
+    pub root: PathBuf,
+    /// Path relative to the library root, with `/` separators
+    pub relative: String,
+    /// Full path of the document
+    pub path: PathBuf,
+}
+
+impl DocumentHandle {
+    /// Handle for a document below the library root
+    pub fn new(lib_root: &Path, root: &Path, path: PathBuf, doc_type: DocumentType) -> Self {
+        let relative = path
+            .strip_prefix(lib_root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        Self {
+            doc_type,
+            root: root.to_path_buf(),
+            relative,
+            path,
+        }
+    }
+}
+
+/// Finds the markdown documents of a library. Every command that scans the library goes
+/// through a walker so ignore rules and traversal policies apply the same everywhere.
+#[derive(Debug, Clone)]
+pub struct Walker {
+    lib_root: PathBuf,
+    config: WalkConfig,
+    max_depth: Option<usize>,
+}
+
+/// A directory to read: its path, the ignore rules for its entries, its depth below the
+/// walk root and the identities of the directories on the way to it, itself included
+type QueuedDir = (PathBuf, Arc<Rules>, usize, Vec<DirId>);
+
+/// Identity of a directory, to notice when symlinks lead back to one being walked
+#[cfg(unix)]
+type DirId = (u64, u64);
+#[cfg(not(unix))]
+type DirId = PathBuf;
+
+/// Directories waiting to be read, shared by the walker threads
+struct WalkQueue {
+    pending: Vec<QueuedDir>,
+    /// Directories being read right now, which may add more to `pending`
+    active: usize,
+}
+
+/// What reading one directory found
+#[derive(Debug, Default)]
+struct DirContents {
+    subdirs: Vec<QueuedDir>,
+    /// Markdown files and the identity of the directory they were found in
+    files: Vec<(PathBuf, Option<DirId>)>,
+    warnings: Vec<String>,
+}
+
+impl Walker {
+    /// Create a walker for a library using its `[walk]` configuration
+    pub fn new(lib_root: &Path, config: &WalkConfig) -> Self {
+        Self {
+            lib_root: lib_root.to_path_buf(),
+            config: config.clone(),
+            max_depth: (config.max_depth > 0).then_some(config.max_depth),
+        }
+    }
+
+    /// Only descend this many directory levels below the walk root (1 reads the root
+    /// directory alone); None walks the whole tree
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// All documents of the library: the docs and templates directories and markdown
+    /// files directly in the library root, each sorted by path
+    pub fn documents(&self) -> io::Result<Vec<DocumentHandle>> {
+        let mut documents = self.walk(Path::new(DOCS_DIR), DocumentType::Document)?;
+        documents.extend(self.walk(Path::new(TEMPLATES_DIR), DocumentType::Template)?);
+
+        let root_depth = self.max_depth.map_or(1, |depth| depth.min(1));
+        documents.extend(
+            self.clone()
+                .max_depth(Some(root_depth))
+                .walk(Path::new(""), DocumentType::Document)?,
+        );
+        Ok(documents)
+    }
+
+    /// The documents below a directory (relative to the library root), sorted by path.
+    /// Directories that cannot be read are reported on stderr and skipped.
+    pub fn walk(&self, root: &Path, doc_type: DocumentType) -> io::Result<Vec<DocumentHandle>> {
+        let (documents, warnings) = self.walk_with_warnings(root, doc_type);
+        for warning in warnings {
+            eprintln!("Warning: {}", warning);
+        }
+        Ok(documents)
+    }
+
+    /// Walk a directory on the configured number of threads, returning the documents
+    /// sorted by path so results do not depend on scheduling, and any warnings
+    fn walk_with_warnings(
+        &self,
+        root: &Path,
+        doc_type: DocumentType,
+    ) -> (Vec<DocumentHandle>, Vec<String>) {
+        let dir = self.lib_root.join(root);
+        if !dir.is_dir() || self.max_depth == Some(0) {
+            return (Vec::new(), Vec::new());
+        }
+        let Some(rules) = Rules::root(&self.lib_root, &self.config).enter_path(&dir) else {
+            return (Vec::new(), Vec::new());
+        };
+
+        let ancestors = dir_id(&dir).into_iter().collect();
+        let queue = Mutex::new(WalkQueue {
+            pending: vec![(dir, rules, 0, ancestors)],
+            active: 0,
+        });
+        let ready = Condvar::new();
+        let found = Mutex::new(DirContents::default());
+
+        // A single directory is read by one thread
+        let threads = if self.max_depth == Some(1) {
+            1
+        } else {
+            parallel::thread_count(self.config.threads)
+        };
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| loop {
+                    let (dir, rules, depth, ancestors) = {
+                        let mut queue = queue.lock().unwrap();
+                        loop {
+                            if let Some(next) = queue.pending.pop() {
+                                queue.active += 1;
+                                break next;
+                            }
+                            if queue.active == 0 {
+                                return;
+                            }
+                            queue = ready.wait(queue).unwrap();
+                        }
+                    };
+
+                    let contents = self.read_directory(&dir, &rules, depth, &ancestors);
+                    {
+                        let mut found = found.lock().unwrap();
+                        found.files.extend(contents.files);
+                        found.warnings.extend(contents.warnings);
+                    }
+                    let mut queue = queue.lock().unwrap();
+                    queue.active -= 1;
+                    queue.pending.extend(contents.subdirs);
+                    ready.notify_all();
+                });
+            }
+        });
+
+        let DirContents {
+            mut files,
+            mut warnings,
+            ..
+        } = found.into_inner().unwrap();
+        files.sort();
+        warnings.sort();
+
+        // A directory reached through several paths (by symlinks) is listed once, under
+        // the first of its paths
+        let mut seen = HashSet::new();
+        let mut documents = Vec::new();
+        for (path, id) in files {
+            if let Some(id) = id {
+                if !seen.insert((id, path.file_name().map(|name| name.to_os_string()))) {
+                    continue;
+                }
+            }
+            documents.push(DocumentHandle::new(&self.lib_root, root, path, doc_type));
+        }
+        (documents, warnings)
+    }
+
+    /// The subdirectories to descend into and the markdown files directly inside a
+    /// directory, leaving out ignored and hidden entries, symlinks unless they are
+    /// followed, and symlinks back to a directory above
+    fn read_directory(
+        &self,
+        dir: &Path,
+        rules: &Arc<Rules>,
+        depth: usize,
+        ancestors: &[DirId],
+    ) -> DirContents {
+        let mut contents = DirContents::default();
+        let id = dir_id(dir);
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                contents
+                    .warnings
+                    .push(format!("cannot read directory {}: {}", dir.display(), err));
+                return contents;
+            }
+        };
+
+        let descend = self.max_depth.map_or(true, |max| depth + 1 < max);
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    contents.warnings.push(format!(
+                        "cannot read directory {}: {}",
+                        dir.display(),
+                        err
+                    ));
+                    continue;
+                }
+            };
+            let path = entry.path();
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if hidden && !self.config.hidden {
+                continue;
+            }
+            let is_symlink = entry.file_type().map(|t| t.is_symlink()).unwrap_or(false);
+            if is_symlink && !self.config.follow_symlinks {
+                continue;
+            }
+
+            if path.is_dir() {
+                if !descend || rules.is_ignored(&path, true) {
+                    continue;
+                }
+                let mut subdir_ancestors = ancestors.to_vec();
+                if let Some(subdir_id) = dir_id(&path) {
+                    if ancestors.contains(&subdir_id) {
+                        contents.warnings.push(format!(
+                            "skipping {}: symbolic link cycle back to a parent directory",
+                            path.display()
+                        ));
+                        continue;
+                    }
+                    subdir_ancestors.push(subdir_id);
+                }
+                let subdir_rules = rules.enter(&path);
+                contents
+                    .subdirs
+                    .push((path, subdir_rules, depth + 1, subdir_ancestors));
+            } else if is_markdown_file(&path) && !rules.is_ignored(&path, false) {
+                contents.files.push((path, id.to_owned()));
+            }
+        }
+        contents
+    }
+}
+
+/// Device and inode of a directory, following symlinks
+#[cfg(unix)]
+fn dir_id(path: &Path) -> Option<DirId> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path)
+        .ok()
+        .map(|metadata| (metadata.dev(), metadata.ino()))
+}
+
+/// Canonical path of a directory, where inodes are not available
+#[cfg(not(unix))]
+fn dir_id(path: &Path) -> Option<DirId> {
+    fs::canonicalize(path).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn relative(documents: &[DocumentHandle]) -> Vec<&str> {
+        documents.iter().map(|doc| doc.relative.as_str()).collect()
+    }
+
+    #[test]
+    fn test_walk() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_walker_walk");
+        let _ = fs::remove_dir_all(&temp_dir);
+        for dir in ["a/b/c", "d", "e"] {
+            fs::create_dir_all(temp_dir.join(dir)).unwrap();
+        }
+        for file in [
+            "top.md",
+            "a/one.md",
+            "a/b/c/deep.md",
+            "d/two.md",
+            "d/notes.txt",
+        ] {
+            fs::write(temp_dir.join(file), "# Doc\n").unwrap();
+        }
+
+        let root = Path::new("");
+        let expected = vec!["a/b/c/deep.md", "a/one.md", "d/two.md", "top.md"];
+        for threads in [1, 4] {
+            let config = WalkConfig {
+                threads,
+                ..WalkConfig::default()
+            };
+            let walker = Walker::new(&temp_dir, &config);
+            let documents = walker.walk(root, DocumentType::Document).unwrap();
+            assert_eq!(relative(&documents), expected);
+        }
+
+        let walker = Walker::new(&temp_dir, &WalkConfig::default());
+        let documents = walker.walk(Path::new("a"), DocumentType::Template).unwrap();
+        assert_eq!(documents[0].root, Path::new("a"));
+        assert_eq!(documents[0].doc_type, DocumentType::Template);
+        assert_eq!(documents[0].path, temp_dir.join("a/b/c/deep.md"));
+        assert!(walker
+            .walk(Path::new("missing"), DocumentType::Document)
+            .unwrap()
+            .is_empty());
+
+        // Depth limits
+        let shallow = walker.clone().max_depth(Some(1));
+        let documents = shallow.walk(root, DocumentType::Document).unwrap();
+        assert_eq!(relative(&documents), vec!["top.md"]);
+        let two_levels = walker.clone().max_depth(Some(2));
+        let documents = two_levels.walk(root, DocumentType::Document).unwrap();
+        assert_eq!(relative(&documents), vec!["a/one.md", "d/two.md", "top.md"]);
+        let config = WalkConfig {
+            max_depth: 2,
+            ..WalkConfig::default()
+        };
+        let configured = Walker::new(&temp_dir, &config);
+        let documents = configured.walk(root, DocumentType::Document).unwrap();
+        assert_eq!(relative(&documents), vec!["a/one.md", "d/two.md", "top.md"]);
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_walk_ignore_and_hidden() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_walker_ignore");
+        let _ = fs::remove_dir_all(&temp_dir);
+        for dir in ["a/b", "d", ".obsidian"] {
+            fs::create_dir_all(temp_dir.join(dir)).unwrap();
+        }
+        for file in [
+            "a/one.md",
+            "a/b/two.md",
+            "d/three.md",
+            ".obsidian/x.md",
+            ".hidden.md",
+        ] {
+            fs::write(temp_dir.join(file), "# Doc\n").unwrap();
+        }
+        fs::write(temp_dir.join("a/.mdlibsignore"), "b/\n").unwrap();
+
+        let config = WalkConfig {
+            exclude: vec![String::from("/d/")],
+            ..WalkConfig::default()
+        };
+        let walker = Walker::new(&temp_dir, &config);
+        let documents = walker.walk(Path::new(""), DocumentType::Document).unwrap();
+        assert_eq!(relative(&documents), vec!["a/one.md"]);
+        assert!(walker
+            .walk(Path::new("d"), DocumentType::Document)
+            .unwrap()
+            .is_empty());
+
+        let config = WalkConfig {
+            hidden: true,
+            ..config
+        };
+        let documents = Walker::new(&temp_dir, &config)
+            .walk(Path::new(""), DocumentType::Document)
+            .unwrap();
+        assert_eq!(
+            relative(&documents),
+            vec![".hidden.md", ".obsidian/x.md", "a/one.md"]
+        );
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_documents() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_walker_documents");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs/guides")).unwrap();
+        fs::create_dir_all(temp_dir.join("templates")).unwrap();
+        fs::create_dir_all(temp_dir.join("other")).unwrap();
+        for file in [
+            "README.md",
+            "docs/guides/setup.md",
+            "templates/note.md",
+            "other/skipped.md",
+        ] {
+            fs::write(temp_dir.join(file), "# Doc\n").unwrap();
+        }
+
+        let documents = Walker::new(&temp_dir, &WalkConfig::default())
+            .documents()
+            .unwrap();
+        let summary: Vec<(&str, DocumentType, &Path)> = documents
+            .iter()
+            .map(|doc| (doc.relative.as_str(), doc.doc_type, doc.root.as_path()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "docs/guides/setup.md",
+                    DocumentType::Document,
+                    Path::new("docs")
+                ),
+                (
+                    "templates/note.md",
+                    DocumentType::Template,
+                    Path::new("templates")
+                ),
+                ("README.md", DocumentType::Document, Path::new("")),
+            ]
+        );
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_symlink_cycle() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = env::temp_dir().join("mdlibs_test_walker_symlinks");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("docs/a")).unwrap();
+        fs::create_dir_all(temp_dir.join("shared")).unwrap();
+        fs::write(temp_dir.join("docs/a/one.md"), "# One\n").unwrap();
+        fs::write(temp_dir.join("shared/two.md"), "# Two\n").unwrap();
+        // A link back up the tree and two links to a directory outside it
+        symlink(temp_dir.join("docs"), temp_dir.join("docs/a/loop")).unwrap();
+        symlink(temp_dir.join("shared"), temp_dir.join("docs/shared")).unwrap();
+        symlink(temp_dir.join("shared"), temp_dir.join("docs/again")).unwrap();
+
+        let walker = Walker::new(&temp_dir, &WalkConfig::default());
+        let (documents, warnings) =
+            walker.walk_with_warnings(Path::new("docs"), DocumentType::Document);
+        // The shared directory is listed once, under its first path, without a warning
+        assert_eq!(
+            relative(&documents),
+            vec!["docs/a/one.md", "docs/again/two.md"]
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("docs/a/loop"));
+
+        let config = WalkConfig {
+            follow_symlinks: false,
+            ..WalkConfig::default()
+        };
+        let walker = Walker::new(&temp_dir, &config);
+        let (documents, warnings) =
+            walker.walk_with_warnings(Path::new("docs"), DocumentType::Document);
+        assert_eq!(relative(&documents), vec!["docs/a/one.md"]);
+        assert!(warnings.is_empty());
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_unreadable_directory_is_a_warning() {
+        let temp_dir = env::temp_dir().join("mdlibs_test_walker_unreadable");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let walker = Walker::new(&temp_dir, &WalkConfig::default());
+        let rules = Rules::root(&temp_dir, &WalkConfig::default());
+        let contents = walker.read_directory(&temp_dir.join("vanished"), &rules, 0, &[]);
+        assert!(contents.files.is_empty());
+        assert_eq!(contents.warnings.len(), 1);
+        assert!(contents.warnings[0].starts_with("cannot read directory"));
+
+        // Cleanup
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}